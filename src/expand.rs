@@ -1,34 +1,61 @@
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use std::collections::{HashMap, hash_map::Entry};
 use syn::ItemStruct;
 
-use crate::resolve::{Builder, BuilderViewField, ViewStructBuilder};
+use crate::parse::{FieldDefault, ViewsConfig};
+use crate::resolve::{
+    Builder, BuilderViewField, ViewStructBuilder, collection_element_type, original_field_names, type_to_string,
+};
 
 pub(crate) fn expand<'a>(
     original_struct: &'a ItemStruct,
     mut builder: Builder<'a>,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let mut generated_code = Vec::new();
+    if builder.config.debug_plan {
+        generated_code.push(generate_view_plan(original_struct, &builder));
+    }
+
+    for view_structs in &mut builder.view_structs {
+        let view_struct =
+            generate_view_struct(
+            original_struct,
+            view_structs,
+            builder.config,
+            &builder.field_defaults,
+            &builder.view_derives,
+        )?;
+        let ref_structs = generate_ref_view_structs_and_methods(view_structs, builder.config)?; // Note: This mutates, order matters
 
-    for mut view_structs in &mut builder.view_structs {
-        let view_struct = generate_view_struct(view_structs)?;
-        let ref_structs = generate_ref_view_structs_and_methods(&mut view_structs)?; // Note: This mutates, order matters
+        let mut_guard = generate_mut_guard(view_structs, builder.config);
 
         generated_code.push(view_struct);
         generated_code.push(ref_structs);
+        generated_code.extend(mut_guard);
+    }
+    if builder.view_structs.iter().any(|e| e.config.field_index) {
+        generated_code.push(generate_field_enum(original_struct));
     }
     let views_enum = generate_views_enum_and_impl(original_struct, &builder)?;
     generated_code.extend(views_enum);
 
     let conversion_impl = generate_original_conversion_methods(original_struct, &builder)?;
     generated_code.push(conversion_impl);
+    generated_code.extend(generate_view_to_original(original_struct, &builder));
+    generated_code.extend(generate_field_assertions(original_struct, &builder));
 
     Ok(quote! {
         #(#generated_code)*
     })
 }
 
-fn generate_view_struct(view_struct: &ViewStructBuilder) -> syn::Result<proc_macro2::TokenStream> {
+fn generate_view_struct(
+    original_struct: &ItemStruct,
+    view_struct: &ViewStructBuilder,
+    config: &ViewsConfig,
+    field_defaults: &[FieldDefault],
+    view_derives: &[syn::Path],
+) -> syn::Result<proc_macro2::TokenStream> {
     let ViewStructBuilder {
         name,
         builder_fields,
@@ -38,15 +65,46 @@ fn generate_view_struct(view_struct: &ViewStructBuilder) -> syn::Result<proc_mac
     } = view_struct;
 
     let mut struct_fields = Vec::new();
+    // The fields of structs deriving serde, which may skip serializing `None`
+    let mut serde_struct_fields = Vec::new();
     for builder_field in builder_fields {
         let vis = builder_field.vis;
-        let field_name = builder_field.name;
+        let field_name = &builder_field.name;
         let ty = &builder_field.regular_struct_field_type;
+        let forwarded_attributes = builder_field
+            .original_attributes
+            .iter()
+            .filter(|attr| {
+                config
+                    .forward_field_attrs
+                    .iter()
+                    .any(|forwarded| attr.path().is_ident(forwarded))
+            })
+            .collect::<Vec<_>>();
+        let skip_serializing = (config.skip_serializing_none
+            && builder_field.is_option
+            && !builder_field.serialize_none)
+            .then(|| quote! { #[serde(skip_serializing_if = "Option::is_none")] });
 
         struct_fields.push(quote! {
+            #(#forwarded_attributes)*
+            #vis #field_name: #ty
+        });
+        serde_struct_fields.push(quote! {
+            #(#forwarded_attributes)*
+            #skip_serializing
             #vis #field_name: #ty
         });
     }
+    let derives_serialize = derives_trait(attributes, "Serialize")
+        || view_derives
+            .iter()
+            .any(|e| e.segments.last().is_some_and(|e| e.ident == "Serialize"));
+    let view_struct_fields = if derives_serialize {
+        &serde_struct_fields
+    } else {
+        &struct_fields
+    };
 
     let generics_clause = if let Some(g) = view_struct.get_regular_generics() {
         let (_, ty_generics, where_generics) = g.split_for_impl();
@@ -55,11 +113,682 @@ fn generate_view_struct(view_struct: &ViewStructBuilder) -> syn::Result<proc_mac
         quote! {}
     };
 
+    // Fallible views are rejected in resolve
+    let builder_derive = config
+        .view_builder
+        .as_ref()
+        .map(|builder_path| quote! { #[derive(#builder_path::Builder)] });
+
+    let (impl_generics, ty_generics, where_clause) =
+        if let Some(generics) = view_struct.get_regular_generics() {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            (Some(impl_generics), Some(ty_generics), Some(where_clause))
+        } else {
+            (None, None, None)
+        };
+
+    let ref_method = config.ref_method.clone().unwrap_or_else(|| format_ident!("as_ref"));
+    let validate_impl = generate_validate_method(view_struct, ViewKind::Owned, &ref_method).map(|validate_method| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #validate_method
+            }
+        }
+    });
+
+    let new_impl = view_struct.config.new.then(|| {
+        let mut params = Vec::new();
+        let mut assignments = Vec::new();
+        for builder_field in builder_fields {
+            let field_name = &builder_field.name;
+            let ty = &builder_field.regular_struct_field_type;
+            match field_defaults.iter().find(|e| &e.field_name == field_name) {
+                Some(field_default) => {
+                    let value = &field_default.value;
+                    assignments.push(quote! { #field_name: #value });
+                }
+                None => {
+                    params.push(quote! { #field_name: #ty });
+                    assignments.push(quote! { #field_name });
+                }
+            }
+        }
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(#(#params),*) -> Self {
+                    Self {
+                        #(#assignments,)*
+                    }
+                }
+            }
+        }
+    });
+
+    // Tuples of the fields would skip the patterns and validations of fallible views
+    let tuple_from_impl = (!view_struct.is_fallible()).then(|| {
+        let field_names = builder_fields.iter().map(|e| &e.name).collect::<Vec<_>>();
+        let field_types = builder_fields.iter().map(|e| &e.regular_struct_field_type).collect::<Vec<_>>();
+        quote! {
+            impl #impl_generics ::core::convert::From<(#(#field_types,)*)> for #name #ty_generics #where_clause {
+                fn from((#(#field_names,)*): (#(#field_types,)*)) -> Self {
+                    Self {
+                        #(#field_names,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let doc_example = config
+        .doc_examples
+        .as_ref()
+        .map(|module| generate_doc_example(original_struct, view_struct, module));
+
+    let repr = view_struct
+        .config
+        .transparent
+        .then(|| quote! { #[repr(transparent)] });
+
+    let hash_impls = generate_hash_ignore_impls(view_struct);
+
+    // `Arbitrary` cannot generate the borrowed fields, so it is only derived on views owning all their fields
+    let has_ref_field = builder_fields.iter().any(|e| e.is_ref);
+    let view_derives = view_derives
+        .iter()
+        .filter(|derive| !(has_ref_field && derive.segments.last().is_some_and(|e| e.ident == "Arbitrary")))
+        .collect::<Vec<_>>();
+    let view_derive = (!view_derives.is_empty()).then(|| quote! { #[derive(#(#view_derives),*)] });
+
+    let into_impl = view_struct.config.into.as_ref().map(|target| {
+        let conversion = match &view_struct.config.into_with {
+            Some(into_with) => quote! { #into_with(view) },
+            None => {
+                let field_names = builder_fields.iter().map(|e| &e.name);
+                quote! {
+                    #target {
+                        #(#field_names: view.#field_names,)*
+                    }
+                }
+            }
+        };
+        quote! {
+            impl #impl_generics ::core::convert::From<#name #ty_generics> for #target #where_clause {
+                fn from(view: #name #ty_generics) -> Self {
+                    #conversion
+                }
+            }
+        }
+    });
+
+    // Collects into the single collection field, validated in resolve, with the other fields defaulted
+    let from_iter_impl = view_struct.config.from_iter.then(|| {
+        let (collection_field, element_type) = builder_fields
+            .iter()
+            .find_map(|e| collection_element_type(&e.regular_struct_field_type).map(|element| (&e.name, element)))
+            .expect("Validated in resolve");
+        let defaulted_fields = builder_fields.iter().map(|e| &e.name).filter(|e| *e != collection_field);
+        quote! {
+            impl #impl_generics ::core::iter::FromIterator<#element_type> for #name #ty_generics #where_clause {
+                fn from_iter<I: ::core::iter::IntoIterator<Item = #element_type>>(iter: I) -> Self {
+                    Self {
+                        #collection_field: ::core::iter::FromIterator::from_iter(iter),
+                        #(#defaulted_fields: ::core::default::Default::default(),)*
+                    }
+                }
+            }
+        }
+    });
+
+    let bench_helpers_impl = config.bench_helpers.then(|| {
+        let field_names = builder_fields.iter().map(|e| &e.name);
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Reads every field through `black_box`, e.g. to anchor a microbenchmark
+                pub fn touch_all_fields(&self) {
+                    #(::core::hint::black_box(&self.#field_names);)*
+                }
+            }
+        }
+    });
+
+    let dto = view_struct
+        .config
+        .dto
+        .as_ref()
+        .map(|dto_name| generate_dto(view_struct, dto_name, &serde_struct_fields));
+
+    let field_index_impl = view_struct
+        .config
+        .field_index
+        .then(|| generate_field_index_impl(original_struct, view_struct));
+
+    let boxed_trait_impl = config
+        .boxed_trait
+        .as_ref()
+        .map(|boxed_trait| generate_boxed_trait_impl(view_struct, boxed_trait));
+
+    let snapshot_struct = view_struct.config.snapshot.then(|| {
+        let snapshot_name = format_ident!("{}Snapshot", name);
+        quote! {
+            /// A copy of the fields of the view, see the `*_snapshot` method of the original struct
+            #[derive(Clone, Copy)]
+            #visibility struct #snapshot_name #generics_clause {
+                #(#struct_fields,)*
+            }
+        }
+    });
+
     Ok(quote! {
         #(#attributes)*
+        #doc_example
+        #view_derive
+        #builder_derive
+        #repr
         #visibility struct #name #generics_clause {
+            #(#view_struct_fields,)*
+        }
+
+        #validate_impl
+        #new_impl
+        #tuple_from_impl
+        #hash_impls
+        #boxed_trait_impl
+        #into_impl
+        #from_iter_impl
+        #bench_helpers_impl
+        #dto
+        #field_index_impl
+        #snapshot_struct
+    })
+}
+
+/// Generate the serde struct of the `dto` option and the conversions between it and the view. The fields
+/// are declared like the view's, validated in resolve to be owned. Converting back to a view with
+/// validations re-checks them with `TryFrom`, returning the DTO on failure.
+fn generate_dto(
+    view_struct: &ViewStructBuilder,
+    dto_name: &syn::Ident,
+    struct_fields: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let name = view_struct.name;
+    let visibility = view_struct.visibility;
+    let (impl_generics, ty_generics, where_clause) = match view_struct.get_regular_generics() {
+        Some(generics) => {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            (Some(impl_generics), Some(ty_generics), Some(where_clause))
+        }
+        None => (None, None, None),
+    };
+    let field_names = view_struct.builder_fields.iter().map(|e| &e.name).collect::<Vec<_>>();
+    // Patterns only constrain the original struct, the DTO holds the matched values like the view
+    let has_validations =
+        view_struct.validation.is_some() || view_struct.builder_fields.iter().any(|e| e.validation.is_some());
+    let from_dto = if has_validations {
+        let field_checks = view_struct.builder_fields.iter().filter_map(|builder_field| {
+            let field_name = &builder_field.name;
+            builder_field.validation.as_ref().map(|validation| {
+                quote! {
+                    if !{
+                        let #field_name = &dto.#field_name;
+                        #validation
+                    } {
+                        return Err(dto);
+                    }
+                }
+            })
+        });
+        let view_check = view_struct.validation.as_ref().map(|_| {
+            quote! {
+                if !view.validate() {
+                    return Err(#dto_name::from(view));
+                }
+            }
+        });
+        quote! {
+            impl #impl_generics ::core::convert::TryFrom<#dto_name #ty_generics> for #name #ty_generics #where_clause {
+                type Error = #dto_name #ty_generics;
+
+                fn try_from(dto: #dto_name #ty_generics) -> Result<Self, Self::Error> {
+                    #(#field_checks)*
+                    let view = Self {
+                        #(#field_names: dto.#field_names,)*
+                    };
+                    #view_check
+                    Ok(view)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics ::core::convert::From<#dto_name #ty_generics> for #name #ty_generics #where_clause {
+                fn from(dto: #dto_name #ty_generics) -> Self {
+                    Self {
+                        #(#field_names: dto.#field_names,)*
+                    }
+                }
+            }
+        }
+    };
+    quote! {
+        /// The fields of the view as a serde struct, e.g. for an API layer
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #visibility struct #dto_name #ty_generics #where_clause {
             #(#struct_fields,)*
         }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for #dto_name #ty_generics #where_clause {
+            fn from(view: #name #ty_generics) -> Self {
+                Self {
+                    #(#field_names: view.#field_names,)*
+                }
+            }
+        }
+
+        #from_dto
+    }
+}
+
+/// Generate the `*Field` enum with a variant per field of the original struct, for the `field_index` option
+fn generate_field_enum(original_struct: &ItemStruct) -> proc_macro2::TokenStream {
+    let vis = &original_struct.vis;
+    let field_enum_name = format_ident!("{}Field", original_struct.ident);
+    let variants = original_field_names(original_struct).into_iter().map(|field_name| {
+        let variant = field_variant_name(&field_name);
+        let doc = format!(" The `{field_name}` field");
+        quote! {
+            #[doc = #doc]
+            #variant
+        }
+    });
+    quote! {
+        /// A field of the original struct
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #field_enum_name {
+            #(#variants,)*
+        }
+    }
+}
+
+/// Generate `Index<*Field>` for the `field_index` option, returning the field as `&dyn Debug`. Indexing by a
+/// field not in the view panics.
+fn generate_field_index_impl(original_struct: &ItemStruct, view_struct: &ViewStructBuilder) -> proc_macro2::TokenStream {
+    let name = view_struct.name;
+    let field_enum_name = format_ident!("{}Field", original_struct.ident);
+    let (impl_generics, ty_generics, where_clause) =
+        if let Some(generics) = view_struct.get_regular_generics() {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            (Some(impl_generics), Some(ty_generics), Some(where_clause))
+        } else {
+            (None, None, None)
+        };
+    // The field types may borrow for the first lifetime of the view
+    let output_lifetime = view_struct
+        .get_regular_generics()
+        .and_then(|generics| generics.lifetimes().next())
+        .map(|e| {
+            let lifetime = &e.lifetime;
+            quote! { + #lifetime }
+        });
+    // Projected tuple elements and nested views are not a whole field of the original struct
+    let arms = view_struct
+        .builder_fields
+        .iter()
+        .filter(|e| e.name == *e.original_name && e.nested.is_none())
+        .map(|builder_field| {
+            let field_name = &builder_field.name;
+            let variant = field_variant_name(field_name);
+            quote! { #field_enum_name::#variant => &self.#field_name }
+        });
+    let panic_message = format!("Field '{{:?}}' is not in view '{name}'");
+    quote! {
+        impl #impl_generics ::core::ops::Index<#field_enum_name> for #name #ty_generics #where_clause {
+            type Output = dyn ::core::fmt::Debug #output_lifetime;
+
+            fn index(&self, field: #field_enum_name) -> &Self::Output {
+                match field {
+                    #(#arms,)*
+                    #[allow(unreachable_patterns)]
+                    _ => panic!(#panic_message, field),
+                }
+            }
+        }
+    }
+}
+
+/// The variant of the `*Field` enum for a field, e.g. `WordsLimit` for `words_limit`
+fn field_variant_name(field_name: &syn::Ident) -> syn::Ident {
+    let field_name = field_name.to_string();
+    let variant = field_name
+        .trim_start_matches("r#")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    format_ident!("{}", variant)
+}
+
+/// Generate `From<View> for Box<dyn Trait>` for the `boxed_trait = Trait` option
+fn generate_boxed_trait_impl(view_struct: &ViewStructBuilder, boxed_trait: &syn::Path) -> proc_macro2::TokenStream {
+    let name = view_struct.name;
+    let ty_generics = view_struct
+        .get_regular_generics()
+        .map(|generics| generics.split_for_impl().1);
+    let mut generics = view_struct.get_regular_generics().cloned().unwrap_or_default();
+    generics.params.insert(0, syn::parse_quote! { 'boxed });
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { #name #ty_generics: 'boxed });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::core::convert::From<#name #ty_generics>
+            for ::std::boxed::Box<dyn #boxed_trait + 'boxed> #where_clause
+        {
+            fn from(view: #name #ty_generics) -> Self {
+                ::std::boxed::Box::new(view)
+            }
+        }
+    }
+}
+
+/// Generate an `# Examples` doc section creating the view from the original struct, with every field of the
+/// original set to its `Default`. Marked `ignore`, since the path of the original struct is unknown here.
+fn generate_doc_example(
+    original_struct: &ItemStruct,
+    view_struct: &ViewStructBuilder,
+    module: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let original_name = original_struct.ident.to_string();
+    let original_variable = pascal_to_snake_case(&original_name);
+    let as_ref_method = format!("as_{}", pascal_to_snake_case(&view_struct.name.to_string()));
+    // Doctests are compiled as another crate, so `crate` is replaced with the name of the crate expanding the macro
+    let crate_name = std::env::var("CARGO_CRATE_NAME")
+        .or_else(|_| std::env::var("CARGO_PKG_NAME").map(|e| e.replace('-', "_")))
+        .unwrap_or_else(|_| "crate".to_owned());
+    let mut import = vec![crate_name];
+    import.extend(module.segments.iter().skip(1).map(|e| e.ident.to_string()));
+    import.push(original_name.clone());
+
+    let mut lines = vec![
+        " # Examples".to_owned(),
+        " ```rust".to_owned(),
+        format!(" use {};", import.join("::")),
+        String::new(),
+        format!(" let {original_variable} = {original_name} {{"),
+    ];
+    for (index, field) in original_struct.fields.iter().enumerate() {
+        match &field.ident {
+            Some(field_name) => lines.push(format!("     {field_name}: Default::default(),")),
+            None => lines.push(format!("     {index}: Default::default(),")),
+        }
+    }
+    lines.push(" };".to_owned());
+    if view_struct.is_fallible() {
+        lines.push(" // `None` unless the patterns and validations of the view hold".to_owned());
+    }
+    lines.push(format!(" let view = {original_variable}.{as_ref_method}();"));
+    lines.push(" ```".to_owned());
+    quote! {
+        #(#[doc = #lines])*
+    }
+}
+
+/// Whether `as_*_mut` wraps the `*Mut` struct in a guard re-checking the validations on drop
+fn uses_mut_guard(view_struct: &ViewStructBuilder, config: &ViewsConfig) -> bool {
+    config.debug_assert_invariants
+        && (view_struct.validation.is_some()
+            || view_struct.builder_fields.iter().any(|e| e.validation.is_some()))
+}
+
+/// Generate a guard for the `*Mut` struct that re-runs the view's validations when dropped in debug builds,
+/// to catch mutations that break the view's invariants
+fn generate_mut_guard(
+    view_struct: &ViewStructBuilder,
+    config: &ViewsConfig,
+) -> Option<proc_macro2::TokenStream> {
+    if !uses_mut_guard(view_struct, config) {
+        return None;
+    }
+    let view_name = view_struct.name;
+    let visibility = view_struct.visibility;
+    let mut_struct_name = format_ident!("{}Mut", view_name);
+    let mut_guard_name = format_ident!("{}MutGuard", view_name);
+
+    let mut checks = Vec::new();
+    for builder_field in &view_struct.builder_fields {
+        let Some(validation) = builder_field.validation else {
+            continue;
+        };
+        let field_name = &builder_field.name;
+        // Bind the same way as during construction
+        let binding = if builder_field.is_ref {
+            quote! { let #field_name = &self.0.#field_name; }
+        } else {
+            quote! { let #field_name = &*self.0.#field_name; }
+        };
+        let message = format!(
+            "Invariant of `{view_name}` violated after mutation: `{}`",
+            quote! { #validation }
+        );
+        checks.push(quote! {
+            {
+                #binding
+                assert!(#validation, #message);
+            }
+        });
+    }
+    if view_struct.validation.is_some() {
+        let message = format!("Invariant of `{view_name}` violated after mutation: view validation");
+        checks.push(quote! {
+            assert!(self.0.validate(), #message);
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) =
+        if let Some(generics) = view_struct.get_ref_generics() {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            (Some(impl_generics), Some(ty_generics), Some(where_clause))
+        } else {
+            (None, None, None)
+        };
+
+    Some(quote! {
+        /// Dereferences to the `*Mut` struct and re-checks the view's validations when dropped in debug builds
+        #visibility struct #mut_guard_name #ty_generics (#mut_struct_name #ty_generics) #where_clause;
+
+        impl #impl_generics ::core::ops::Deref for #mut_guard_name #ty_generics #where_clause {
+            type Target = #mut_struct_name #ty_generics;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::core::ops::DerefMut for #mut_guard_name #ty_generics #where_clause {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl #impl_generics ::core::ops::Drop for #mut_guard_name #ty_generics #where_clause {
+            fn drop(&mut self) {
+                #[cfg(debug_assertions)]
+                if !::std::thread::panicking() {
+                    #(#checks)*
+                }
+            }
+        }
+    })
+}
+
+/// Generate the assertions of the validations of the fields of `view`, an owned view, and of its nested views
+fn generate_invariant_assertions(
+    view: proc_macro2::TokenStream,
+    builder_fields: &[BuilderViewField],
+    view_name: &syn::Ident,
+) -> Vec<proc_macro2::TokenStream> {
+    let mut assertions = Vec::new();
+    for builder_field in builder_fields {
+        let field_name = &builder_field.name;
+        if let Some(nested) = &builder_field.nested {
+            assertions.extend(generate_invariant_assertions(
+                quote! { #view.#field_name },
+                &nested.builder_fields,
+                view_name,
+            ));
+            if nested.validation.is_some() {
+                let message = format!(
+                    "Invariant of `{view_name}` violated after mutation: validation of `{field_name}`"
+                );
+                assertions.push(quote! {
+                    assert!(#view.#field_name.validate(), #message);
+                });
+            }
+            continue;
+        }
+        let Some(validation) = builder_field.validation else {
+            continue;
+        };
+        let message = format!(
+            "Invariant of `{view_name}` violated after mutation: `{}`",
+            quote! { #validation }
+        );
+        assertions.push(quote! {
+            {
+                let #field_name = &#view.#field_name;
+                assert!(#validation, #message);
+            }
+        });
+    }
+    assertions
+}
+
+/// Generate the guard returned by the `*_mut` accessors of the variant enum with `guarded_mut`, which
+/// re-checks the validations of the active view when dropped in debug builds
+fn generate_variant_field_guard(vis: &syn::Visibility, guard_name: &syn::Ident) -> proc_macro2::TokenStream {
+    quote! {
+        /// Dereferences to a field of the active view and re-checks the view's validations when dropped in
+        /// debug builds
+        #vis struct #guard_name<'guard, E, T: ?Sized> {
+            variant: &'guard mut E,
+            get: fn(&E) -> Option<&T>,
+            get_mut: fn(&mut E) -> Option<&mut T>,
+            #[cfg_attr(not(debug_assertions), allow(dead_code))]
+            check: fn(&E),
+        }
+
+        impl<E, T: ?Sized> ::core::ops::Deref for #guard_name<'_, E, T> {
+            type Target = T;
+
+            fn deref(&self) -> &Self::Target {
+                (self.get)(&*self.variant).expect("The view of the variant does not change while guarded")
+            }
+        }
+
+        impl<E, T: ?Sized> ::core::ops::DerefMut for #guard_name<'_, E, T> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                (self.get_mut)(&mut *self.variant).expect("The view of the variant does not change while guarded")
+            }
+        }
+
+        impl<E, T: ?Sized> ::core::ops::Drop for #guard_name<'_, E, T> {
+            fn drop(&mut self) {
+                #[cfg(debug_assertions)]
+                if !::std::thread::panicking() {
+                    (self.check)(&*self.variant);
+                }
+            }
+        }
+    }
+}
+
+/// Generate `Hash`, `PartialEq`, and `Eq` impls skipping the fields in `#[view(hash_ignore = ..)]`
+fn generate_hash_ignore_impls(view_struct: &ViewStructBuilder) -> Option<proc_macro2::TokenStream> {
+    let hash_ignore = &view_struct.config.hash_ignore;
+    if hash_ignore.is_empty() {
+        return None;
+    }
+    let name = view_struct.name;
+    let (impl_generics, ty_generics, where_clause) =
+        if let Some(generics) = view_struct.get_regular_generics() {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            (Some(impl_generics), Some(ty_generics), Some(where_clause))
+        } else {
+            (None, None, None)
+        };
+    let fields = view_struct
+        .builder_fields
+        .iter()
+        .map(|e| &e.name)
+        .filter(|name| !hash_ignore.iter().any(|ignored| ignored == *name))
+        .collect::<Vec<_>>();
+    Some(quote! {
+        impl #impl_generics ::core::hash::Hash for #name #ty_generics #where_clause {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                #(::core::hash::Hash::hash(&self.#fields, state);)*
+            }
+        }
+
+        impl #impl_generics ::core::cmp::PartialEq for #name #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#fields == other.#fields)*
+            }
+        }
+
+        impl #impl_generics ::core::cmp::Eq for #name #ty_generics #where_clause {}
+    })
+}
+
+/// Which of the generated structs of a view is being generated for
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ViewKind {
+    Owned,
+    Ref,
+    Mut,
+}
+
+/// Generate the `validate` method for a view-level validation, if any. Every field is bound by name as
+/// a shared reference, the same way for the owned, `*Ref`, and `*Mut` structs. Nested views are bound as
+/// their `*Ref` struct.
+fn generate_validate_method(
+    view_struct: &ViewStructBuilder,
+    kind: ViewKind,
+    ref_method: &syn::Ident,
+) -> Option<proc_macro2::TokenStream> {
+    let validation = view_struct.validation.as_ref()?;
+    let bindings = view_struct.builder_fields.iter().map(|builder_field| {
+        let field_name = &builder_field.name;
+        if builder_field.nested.is_some() {
+            match kind {
+                ViewKind::Owned => quote! { let #field_name = &self.#field_name.#ref_method(); },
+                ViewKind::Ref => quote! { let #field_name = &self.#field_name; },
+                ViewKind::Mut => quote! { let #field_name = &self.#field_name.downgrade(); },
+            }
+        } else if (kind == ViewKind::Owned && !builder_field.is_ref)
+            || (kind == ViewKind::Ref && builder_field.copy_in_ref)
+            // A `ref_type` projection need not be a reference
+            || (kind == ViewKind::Ref
+                && builder_field.ref_expr.is_some()
+                && !matches!(builder_field.ref_struct_field_type, syn::Type::Reference(_)))
+        {
+            quote! { let #field_name = &self.#field_name; }
+        } else {
+            quote! { let #field_name = &*self.#field_name; }
+        }
+    });
+    Some(quote! {
+        /// Runs the view-level validation, e.g. to re-validate after mutation
+        #[allow(unused_variables)]
+        pub fn validate(&self) -> bool {
+            #(#bindings)*
+            #validation
+        }
     })
 }
 
@@ -89,26 +818,85 @@ fn generate_views_enum_and_impl(
         semi_token: _,
     } = original_struct;
 
+    // Views borrowing from the original struct hold the lifetime of the borrow, so the enum does too
+    let mut enum_generics = generics.clone();
+    if builder.view_structs.iter().any(|e| e.borrows_original()) {
+        let original_lifetime = &builder.original_lifetime;
+        enum_generics.params.insert(0, syn::parse_quote!(#original_lifetime));
+    }
+    let generics = &enum_generics;
+
     let mut enum_name = ident.to_string();
     enum_name.push_str("Variant");
     let enum_name = syn::Ident::new(enum_name.as_str(), ident.span());
 
     let attrs = &builder.enum_attributes;
+    // Lets `vtable` read the discriminant to index its tables
+    let repr = builder.variant_config.vtable.then(|| quote! { #[repr(u8)] });
+    // Every variant holds a view struct, which serde can internally tag
+    let serde_derive = builder.variant_config.serde_tag.as_ref().map(|tag| {
+        quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+            #[serde(tag = #tag)]
+        }
+    });
 
     let mut tokens = Vec::new();
 
+    let enum_where_clause = &generics.where_clause;
     tokens.push(quote! {
         #(#attrs)*
-        #vis enum #enum_name #generics {
+        #serde_derive
+        #repr
+        #[allow(clippy::enum_variant_names)]
+        #vis enum #enum_name #generics #enum_where_clause {
             #(#branches,)*
         }
     });
 
+    if builder.variant_config.static_alias {
+        if generics.lifetimes().next().is_none() {
+            return Err(syn::Error::new(
+                ident.span(),
+                "`static_alias` requires the original struct to have a lifetime parameter",
+            ));
+        }
+        let alias_name = format_ident!("{}Static", enum_name);
+        // The remaining parameters are declared without bounds, which type aliases do not check
+        let alias_params = generics.params.iter().filter_map(|param| match param {
+            syn::GenericParam::Lifetime(_) => None,
+            syn::GenericParam::Type(type_param) => {
+                let ident = &type_param.ident;
+                Some(quote! { #ident })
+            }
+            syn::GenericParam::Const(const_param) => {
+                let ident = &const_param.ident;
+                let ty = &const_param.ty;
+                Some(quote! { const #ident: #ty })
+            }
+        });
+        let enum_args = generics.params.iter().map(|param| match param {
+            syn::GenericParam::Lifetime(_) => quote! { 'static },
+            syn::GenericParam::Type(type_param) => {
+                let ident = &type_param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Const(const_param) => {
+                let ident = &const_param.ident;
+                quote! { #ident }
+            }
+        });
+        tokens.push(quote! {
+            /// The variant enum with every lifetime `'static`
+            #vis type #alias_name<#(#alias_params),*> = #enum_name<#(#enum_args),*>;
+        });
+    }
+
     // Determine the common types for fields - what should be the return type of the variant methods
     let mut common_types_for_fields = HashMap::new();
 
     for field in builder.view_structs.iter().flat_map(|e| &e.builder_fields) {
-        let entry = common_types_for_fields.entry(field.name);
+        let entry = common_types_for_fields.entry(&field.name);
         match entry {
             Entry::Occupied(mut occupied_entry) => {
                 let current_common_ty: &mut CommmonType = occupied_entry.get_mut();
@@ -122,10 +910,12 @@ fn generate_views_enum_and_impl(
             Entry::Vacant(vacant_entry) => {
                 let common_type = CommmonType {
                     stripped_type: &field.stripped_type,
+                    getter: &field.getter,
                     is_there_an_option: field.is_option,
                     is_there_an_owned: !field.is_ref,
                     is_there_a_ref: field.is_ref,
                     is_there_a_mut: field.is_mut,
+                    is_boxed: field.is_stripped_type_boxed,
                 };
                 vacant_entry.insert(common_type);
             }
@@ -133,7 +923,7 @@ fn generate_views_enum_and_impl(
     }
     for (name, common_ty) in common_types_for_fields.iter_mut() { 
         for view_struct in builder.view_structs.iter() {
-            if !view_struct.builder_fields.iter().any(|e| &e.name == name) {
+            if !view_struct.builder_fields.iter().any(|e| e.name == **name) {
                 // At least one view does not contain these field so we need option
                 common_ty.is_there_an_option = true;
             }
@@ -141,65 +931,218 @@ fn generate_views_enum_and_impl(
     }
 
     let mut methods = Vec::new();
-    let mut ref_field_to_arms = HashMap::new();
+
+    // Generate view predicates and borrowing downcasts
     for view in &builder.view_structs {
         let view_name = view.name;
-        for field in view.builder_fields.iter() {
-            let arms_of_field = ref_field_to_arms
-                .entry(&field.name)
-                .or_insert_with(|| Vec::new());
-
-            let target_common_type = common_types_for_fields.get(&field.name).unwrap();
-
-            let name = &field.name;
-
-            // Add ref arms
-            if target_common_type.is_there_an_option {
-                if field.is_option {
-                    if field.is_stripped_type_ref {
-                        arms_of_field.push(quote! {
-                            #enum_name::#view_name(view) => view.#name
-                        });
-                    }
-                    else {
-                        arms_of_field.push(quote! {
-                            #enum_name::#view_name(view) => view.#name.as_ref()
-                        });
-                    }
-                }
-                else {
-                    arms_of_field.push(quote! {
-                        #enum_name::#view_name(view) => Some(&view.#name)
-                    });
+        let snake_case_name = pascal_to_snake_case(&view_name.to_string());
+        let is_method = format_ident!("is_{}", snake_case_name);
+        methods.push(quote! {
+            pub fn #is_method(&self) -> bool {
+                matches!(self, #enum_name::#view_name(_))
+            }
+        });
+        let view_ty_generics = view.get_regular_generics().map(|e| {
+            let (_, ty_generics, _) = e.split_for_impl();
+            ty_generics
+        });
+        let as_method = format_ident!("as_{}", snake_case_name);
+        let as_mut_method = format_ident!("as_{}_mut", snake_case_name);
+        methods.push(quote! {
+            pub fn #as_method(&self) -> Option<&#view_name #view_ty_generics> {
+                match self {
+                    #enum_name::#view_name(view) => Some(view),
+                    #[allow(unreachable_patterns)]
+                    _ => None,
                 }
-            } else {
-                arms_of_field.push(quote! {
-                    #enum_name::#view_name(view) => &view.#name
-                });
             }
 
-            let can_add_mut_method = !target_common_type.is_there_a_ref;
-
-            if can_add_mut_method {
-                // todo *_mut field accessors
+            pub fn #as_mut_method(&mut self) -> Option<&mut #view_name #view_ty_generics> {
+                match self {
+                    #enum_name::#view_name(view) => Some(view),
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        });
+    }
+
+    // Fieldless kind of each view, e.g. for listing views without holding one
+    let kind_name = format_ident!("{}Kind", enum_name);
+    let kind_variants = builder.view_structs.iter().map(|e| &e.name).collect::<Vec<_>>();
+    tokens.push(quote! {
+        /// The kind of view held by the variant enum
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[allow(clippy::enum_variant_names)]
+        #vis enum #kind_name {
+            #(#kind_variants,)*
+        }
+    });
+    // Skipped if a field accessor is named `kind`
+    if !common_types_for_fields.values().any(|e| *e.getter == "kind") {
+        methods.push(quote! {
+            pub fn kind(&self) -> #kind_name {
+                match self {
+                    #(#enum_name::#kind_variants(_) => #kind_name::#kind_variants,)*
+                }
+            }
+        });
+    }
+
+    // Numeric tag of each view for FFI, skipped if a field accessor is named `tag`
+    if kind_variants.len() > 256 {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "The variant enum supports at most 256 views, since `tag` returns a `u8`, found {}",
+                kind_variants.len()
+            ),
+        ));
+    }
+    if !common_types_for_fields.values().any(|e| *e.getter == "tag") {
+        let tags = (0..kind_variants.len()).map(|e| e as u8);
+        methods.push(quote! {
+            /// The index of the view in declaration order, e.g. for FFI. Stable as long as views are only
+            /// appended
+            pub fn tag(&self) -> u8 {
+                match self {
+                    #(#enum_name::#kind_variants(_) => #tags,)*
+                }
+            }
+        });
+    }
+
+    let mut ref_field_to_arms = HashMap::new();
+    let mut mut_field_to_arms = HashMap::new();
+    let mut owned_field_to_arms = HashMap::new();
+    let mut field_to_projections = HashMap::new();
+    for view in &builder.view_structs {
+        let view_name = view.name;
+        for field in view.builder_fields.iter() {
+            let target_common_type = common_types_for_fields.get(&field.name).unwrap();
+
+            let name = &field.name;
+
+            // Add ref arms
+            let projection = if field.is_stripped_type_boxed {
+                // Borrow the trait object through the box, e.g. `&dyn Fn()` instead of `&Box<dyn Fn()>`
+                if !target_common_type.is_there_an_option {
+                    quote! { &*view.#name }
+                } else if field.is_option {
+                    quote! { view.#name.as_deref() }
+                } else {
+                    quote! { Some(&*view.#name) }
+                }
+            } else if target_common_type.is_there_an_option {
+                if field.is_option {
+                    if field.is_stripped_type_ref {
+                        quote! { view.#name }
+                    }
+                    else {
+                        quote! { view.#name.as_ref() }
+                    }
+                }
+                else {
+                    quote! { Some(&view.#name) }
+                }
+            } else {
+                quote! { &view.#name }
+            };
+            ref_field_to_arms.entry(&field.name).or_insert_with(Vec::new).push(quote! {
+                #enum_name::#view_name(view) => #projection
+            });
+            // The projection out of each view, for the table of `vtable`
+            field_to_projections
+                .entry(&field.name)
+                .or_insert_with(Vec::new)
+                .push((view_name, projection));
+
+            // A field borrowed by any view can not be borrowed mutably through the enum
+            let can_add_mut_method = !target_common_type.is_there_a_ref;
+
+            if can_add_mut_method {
+                let mut_arm = if field.is_stripped_type_boxed {
+                    if !target_common_type.is_there_an_option {
+                        quote! { #enum_name::#view_name(view) => &mut *view.#name }
+                    } else if field.is_option {
+                        // `&mut` is invariant, so the trait object's lifetime is shortened per element
+                        quote! { #enum_name::#view_name(view) => view.#name.as_deref_mut().map(|e| e as _) }
+                    } else {
+                        quote! { #enum_name::#view_name(view) => Some(&mut *view.#name) }
+                    }
+                } else if target_common_type.is_there_an_option {
+                    if field.is_option {
+                        quote! { #enum_name::#view_name(view) => view.#name.as_mut() }
+                    } else {
+                        quote! { #enum_name::#view_name(view) => Some(&mut view.#name) }
+                    }
+                } else {
+                    quote! { #enum_name::#view_name(view) => &mut view.#name }
+                };
+                mut_field_to_arms.entry(&field.name).or_insert_with(Vec::new).push(mut_arm);
             }
 
             let can_add_owned_method =
                 !target_common_type.is_there_a_ref && !target_common_type.is_there_a_mut;
 
             if can_add_owned_method {
-                // todo into_* field accessors
+                // `Option` fields move out their value, without cloning
+                let owned_arm = if target_common_type.is_there_an_option && !field.is_option {
+                    quote! { #enum_name::#view_name(view) => Some(view.#name) }
+                } else {
+                    quote! { #enum_name::#view_name(view) => view.#name }
+                };
+                owned_field_to_arms.entry(&field.name).or_insert_with(Vec::new).push(owned_arm);
             }
         }
     }
 
-    for (name,target_common_type) in common_types_for_fields.iter() {
+    // Accessors are emitted in `field_order`, then in declaration order of the original fields they read,
+    // e.g. `query` for `text = query`. Nested views are not fields of the original struct.
+    let field_order = &builder.variant_config.field_order;
+    let original_names = original_field_names(original_struct);
+    let mut declaration_order = Vec::new();
+    for field in builder.view_structs.iter().flat_map(|e| &e.builder_fields) {
+        if field.nested.is_some() || declaration_order.iter().any(|(name, _)| *name == &field.name) {
+            continue;
+        }
+        let Some(position) = original_names.iter().position(|e| e == field.original_name) else {
+            continue;
+        };
+        declaration_order.push((&field.name, position));
+    }
+    declaration_order.sort_by_key(|(_, position)| *position);
+    let mut accessor_fields = declaration_order
+        .into_iter()
+        .filter_map(|(name, _)| common_types_for_fields.get_key_value(name))
+        .collect::<Vec<_>>();
+    accessor_fields.sort_by_key(|(name, _)| {
+        field_order
+            .iter()
+            .position(|e| e == **name)
+            .unwrap_or(field_order.len())
+    });
+
+    // The fields whose accessors return them without an `Option`, so are present in every view
+    if !common_types_for_fields.values().any(|e| *e.getter == "common_field_names") {
+        let common_field_names = accessor_fields
+            .iter()
+            .filter(|(_, common_type)| !common_type.is_there_an_option)
+            .map(|(name, _)| name.to_string());
+        methods.push(quote! {
+            /// The names of the fields present in every view, whose accessors do not return an `Option`
+            pub fn common_field_names() -> &'static [&'static str] {
+                &[#(#common_field_names),*]
+            }
+        });
+    }
+
+    let mut accessor_return_types = HashMap::new();
+    for (name, target_common_type) in accessor_fields {
         let arms = ref_field_to_arms.get(name).unwrap();
+        let getter = target_common_type.getter;
         let stripped_type = target_common_type.stripped_type;
-        let is_ref = match stripped_type {
-            syn::Type::Reference(_) => true,
-            _ => false,
-        };
+        let is_ref = matches!(stripped_type, syn::Type::Reference(_));
         let ref_token = if is_ref {
             quote! {}
         }
@@ -207,25 +1150,239 @@ fn generate_views_enum_and_impl(
             quote! {&}
         };
 
-        // Generate ref method
-        if target_common_type.is_there_an_option {
-            methods.push(quote! {
-                pub fn #name(&self) -> Option<#ref_token #stripped_type> {
-                    match self {
-                        #(#arms,)*
-                        _ => None,
+        let return_type = if target_common_type.is_there_an_option {
+            quote! { Option<#ref_token #stripped_type> }
+        } else {
+            quote! { #ref_token #stripped_type }
+        };
+        let body = if builder.variant_config.vtable {
+            // A projection per view, indexed by the `repr(u8)` discriminant rather than matched on
+            let projections = field_to_projections.get(name).unwrap();
+            let table_name = syn::Ident::new(
+                &format_ident!("{}_table", getter).to_string().to_uppercase(),
+                getter.span(),
+            );
+            let mut table = Vec::new();
+            for view in &builder.view_structs {
+                let view_name = view.name;
+                let projection_method =
+                    format_ident!("{}_of_{}", getter, pascal_to_snake_case(&view_name.to_string()));
+                let projection_body = match projections.iter().find(|(e, _)| *e == view_name) {
+                    Some((_, projection)) => quote! {
+                        let #enum_name::#view_name(view) = this else {
+                            unreachable!()
+                        };
+                        #projection
+                    },
+                    None => quote! { None },
+                };
+                methods.push(quote! {
+                    #[allow(irrefutable_let_patterns)]
+                    fn #projection_method(this: &Self) -> #return_type {
+                        #projection_body
                     }
-                }
+                });
+                table.push(projection_method);
+            }
+            let view_count = builder.view_structs.len();
+            methods.push(quote! {
+                const #table_name: [fn(&Self) -> #return_type; #view_count] = [#(Self::#table,)*];
             });
+            quote! {
+                // SAFETY: a `repr(u8)` enum is a `repr(C)` union of `repr(C)` structs that each start with the
+                // `u8` discriminant, which counts the variants from 0 in declaration order
+                let discriminant = unsafe { *(self as *const Self).cast::<u8>() };
+                Self::#table_name[discriminant as usize](self)
+            }
+        } else if target_common_type.is_there_an_option {
+            quote! {
+                match self {
+                    #(#arms,)*
+                    _ => None,
+                }
+            }
         } else {
+            quote! {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        };
+
+        // Generate ref method
+        methods.push(quote! {
+            pub fn #getter(&self) -> #return_type {
+                #body
+            }
+        });
+        // Dispatched with a `match` even with `vtable`, which only covers the shared accessors
+        let mut_method = format_ident!("{}_mut", getter);
+        // Skipped if it would clash with the accessor of another field, e.g. `opt_mut` for `opt`
+        let mut_arms = mut_field_to_arms
+            .get(name)
+            .filter(|_| !common_types_for_fields.values().any(|e| *e.getter == mut_method));
+        if let Some(mut_arms) = mut_arms {
+            let (mut_return_type, fallback) = if target_common_type.is_there_an_option {
+                (quote! { Option<&mut #stripped_type> }, Some(quote! { _ => None, }))
+            } else {
+                (quote! { &mut #stripped_type }, None)
+            };
+            if builder.variant_config.guarded_mut {
+                let guard_name = format_ident!("{}FieldMut", enum_name);
+                let guard_type = quote! { #guard_name<'_, Self, #stripped_type> };
+                // The guard borrows the field through these on every access, so it can re-check the whole view
+                let (guard_return_type, get, get_mut, guard) = if target_common_type.is_there_an_option {
+                    (
+                        quote! { Option<#guard_type> },
+                        quote! { |variant| variant.#getter() },
+                        quote! { |variant| match variant { #(#mut_arms,)* #fallback } },
+                        quote! {
+                            get_mut(self)?;
+                            Some(#guard_name { variant: self, get, get_mut, check: Self::assert_invariants })
+                        },
+                    )
+                } else {
+                    (
+                        guard_type,
+                        quote! { |variant| Some(variant.#getter()) },
+                        quote! { |variant| Some(match variant { #(#mut_arms,)* }) },
+                        quote! { #guard_name { variant: self, get, get_mut, check: Self::assert_invariants } },
+                    )
+                };
+                methods.push(quote! {
+                    pub fn #mut_method(&mut self) -> #guard_return_type {
+                        let get: fn(&Self) -> Option<&#stripped_type> = #get;
+                        let get_mut: fn(&mut Self) -> Option<&mut #stripped_type> = #get_mut;
+                        #guard
+                    }
+                });
+            } else {
+                methods.push(quote! {
+                    pub fn #mut_method(&mut self) -> #mut_return_type {
+                        match self {
+                            #(#mut_arms,)*
+                            #fallback
+                        }
+                    }
+                });
+            }
+        }
+        let into_method = format_ident!("into_{}", getter);
+        let owned_arms = owned_field_to_arms
+            .get(name)
+            .filter(|_| !common_types_for_fields.values().any(|e| *e.getter == into_method));
+        if let Some(owned_arms) = owned_arms {
+            let owned_type = if target_common_type.is_boxed {
+                quote! { ::std::boxed::Box<#stripped_type> }
+            } else {
+                quote! { #stripped_type }
+            };
+            let (owned_return_type, fallback) = if target_common_type.is_there_an_option {
+                (quote! { Option<#owned_type> }, Some(quote! { _ => None, }))
+            } else {
+                (owned_type, None)
+            };
             methods.push(quote! {
-                pub fn #name(&self) -> #ref_token #stripped_type {
+                pub fn #into_method(self) -> #owned_return_type {
                     match self {
-                        #(#arms,)*
+                        #(#owned_arms,)*
+                        #fallback
                     }
                 }
             });
         }
+        accessor_return_types.insert(*name, (getter, return_type.clone()));
+        if target_common_type.is_there_an_option {
+            // The value or a default, for when the field is absent
+            let or_method = format_ident!("{}_or", getter);
+            if is_ref {
+                methods.push(quote! {
+                    pub fn #or_method(&self, default: #stripped_type) -> #stripped_type {
+                        self.#getter().unwrap_or(default)
+                    }
+                });
+            } else {
+                methods.push(quote! {
+                    pub fn #or_method<'view>(&'view self, default: &'view #stripped_type) -> &'view #stripped_type {
+                        self.#getter().unwrap_or(default)
+                    }
+                });
+            }
+        }
+    }
+
+    if builder.config.bench_helpers {
+        let getters = original_names
+            .iter()
+            .filter_map(|name| accessor_return_types.get(name))
+            .map(|(getter, _)| getter);
+        methods.push(quote! {
+            /// Calls every field accessor through `black_box`, e.g. to benchmark their dispatch
+            pub fn touch_all_fields(&self) {
+                #(::core::hint::black_box(self.#getters());)*
+            }
+        });
+    }
+
+    // The accessors of the fields in every view, in declaration order, skipped if a field accessor is named
+    // `common_fields`
+    let (common_getters, common_return_types): (Vec<_>, Vec<_>) = original_names
+        .iter()
+        .filter(|name| {
+            builder
+                .view_structs
+                .iter()
+                .all(|view| view.builder_fields.iter().any(|e| e.name == **name))
+        })
+        .filter_map(|name| accessor_return_types.get(name).cloned())
+        .unzip();
+    if !common_getters.is_empty() && !common_types_for_fields.values().any(|e| *e.getter == "common_fields") {
+        methods.push(quote! {
+            /// The fields in every view, in declaration order
+            pub fn common_fields(&self) -> (#(#common_return_types,)*) {
+                (#(self.#common_getters(),)*)
+            }
+        });
+    }
+
+    if builder.variant_config.field_set {
+        tokens.extend(generate_field_set(original_struct, builder, &enum_name, &mut methods));
+    }
+
+    // Visitor trait with a method per view, for exhaustive dispatch
+    if builder.variant_config.visitor {
+        let visitor_name = format_ident!("{}Visitor", enum_name);
+        let mut visit_methods = Vec::new();
+        let mut visit_arms = Vec::new();
+        for view in &builder.view_structs {
+            let view_name = view.name;
+            let view_ty_generics = view.get_regular_generics().map(|e| {
+                let (_, ty_generics, _) = e.split_for_impl();
+                ty_generics
+            });
+            let visit_method = format_ident!("visit_{}", pascal_to_snake_case(&view_name.to_string()));
+            visit_methods.push(quote! {
+                fn #visit_method(&mut self, view: #view_name #view_ty_generics);
+            });
+            visit_arms.push(quote! {
+                #enum_name::#view_name(view) => visitor.#visit_method(view)
+            });
+        }
+        let (_, reg_ty_generics, _) = generics.split_for_impl();
+        methods.push(quote! {
+            /// Calls the method of the visitor for the view this holds
+            pub fn accept(self, visitor: &mut impl #visitor_name #reg_ty_generics) {
+                match self {
+                    #(#visit_arms,)*
+                }
+            }
+        });
+        let visitor_where_clause = &generics.where_clause;
+        tokens.push(quote! {
+            #vis trait #visitor_name #generics #visitor_where_clause {
+                #(#visit_methods)*
+            }
+        });
     }
 
     let (impl_ty, reg_ty, where_ty,) = generics.split_for_impl();
@@ -235,39 +1392,274 @@ fn generate_views_enum_and_impl(
         }
     });
 
+    // Conversions of each view into the enum, and downcasts of the enum to each view, returning the enum on
+    // mismatch. The enum is generic over every parameter of the original struct, so it constrains them all.
+    for view in &builder.view_structs {
+        let view_name = view.name;
+        let view_ty_generics = view.get_regular_generics().map(|e| {
+            let (_, ty_generics, _) = e.split_for_impl();
+            ty_generics
+        });
+        tokens.push(quote! {
+            impl #impl_ty ::core::convert::From<#view_name #view_ty_generics> for #enum_name #reg_ty #where_ty {
+                fn from(view: #view_name #view_ty_generics) -> Self {
+                    #enum_name::#view_name(view)
+                }
+            }
+        });
+        tokens.push(quote! {
+            impl #impl_ty ::core::convert::TryFrom<#enum_name #reg_ty> for #view_name #view_ty_generics #where_ty {
+                type Error = #enum_name #reg_ty;
+
+                fn try_from(value: #enum_name #reg_ty) -> Result<Self, Self::Error> {
+                    match value {
+                        #enum_name::#view_name(view) => Ok(view),
+                        #[allow(unreachable_patterns)]
+                        other => Err(other),
+                    }
+                }
+            }
+        });
+    }
+
+    if builder.variant_config.guarded_mut {
+        let arms = builder.view_structs.iter().map(|view| {
+            let view_name = view.name;
+            let mut assertions =
+                generate_invariant_assertions(quote! { view }, &view.builder_fields, view_name);
+            if view.validation.is_some() {
+                let message = format!("Invariant of `{view_name}` violated after mutation: view validation");
+                assertions.push(quote! {
+                    assert!(view.validate(), #message);
+                });
+            }
+            quote! {
+                #enum_name::#view_name(view) => {
+                    #(#assertions)*
+                }
+            }
+        });
+        tokens.push(generate_variant_field_guard(vis, &format_ident!("{}FieldMut", enum_name)));
+        tokens.push(quote! {
+            impl #impl_ty #enum_name #reg_ty #where_ty {
+                /// Asserts the validations of the active view, which the guards of the `*_mut` accessors run
+                /// when dropped
+                #[cfg_attr(not(debug_assertions), allow(dead_code))]
+                #[allow(unused_variables)]
+                fn assert_invariants(&self) {
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        });
+    }
+
+    // Views not known to be `Hash` only contribute their discriminant, which is still consistent with `Eq`
+    if builder.variant_config.hash {
+        let arms = builder.view_structs.iter().map(|view| {
+            let view_name = view.name;
+            let is_hash = derives_trait(view.attributes, "Hash")
+                || !view.config.hash_ignore.is_empty()
+                || builder
+                    .view_derives
+                    .iter()
+                    .any(|e| e.segments.last().is_some_and(|e| e.ident == "Hash"));
+            if is_hash {
+                quote! {
+                    #enum_name::#view_name(view) => ::core::hash::Hash::hash(view, state)
+                }
+            } else {
+                quote! {
+                    #enum_name::#view_name(_) => {}
+                }
+            }
+        });
+        tokens.push(quote! {
+            impl #impl_ty ::core::hash::Hash for #enum_name #reg_ty #where_ty {
+                fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                    ::core::hash::Hash::hash(&::core::mem::discriminant(self), state);
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        });
+    }
+
+    if builder.variant_config.debug_transparent {
+        let arms = builder.view_structs.iter().map(|view| {
+            let view_name = view.name;
+            quote! {
+                #enum_name::#view_name(view) => ::core::fmt::Debug::fmt(view, f)
+            }
+        });
+        tokens.push(quote! {
+            impl #impl_ty ::core::fmt::Debug for #enum_name #reg_ty #where_ty {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        });
+    }
+
     Ok(tokens)
 }
 
+/// Generate a bitset of the fields of the original struct, e.g. `SearchFieldSet`, with a const per field, and
+/// the `fields_present` method of the variant enum returning the fields of the view it holds
+fn generate_field_set(
+    original_struct: &ItemStruct,
+    builder: &Builder<'_>,
+    enum_name: &syn::Ident,
+    methods: &mut Vec<proc_macro2::TokenStream>,
+) -> Option<proc_macro2::TokenStream> {
+    let field_names = original_field_names(original_struct);
+    let bits_type = match field_names.len() {
+        0..=8 => quote! { u8 },
+        9..=16 => quote! { u16 },
+        17..=32 => quote! { u32 },
+        33..=64 => quote! { u64 },
+        65..=128 => quote! { u128 },
+        _ => return None,
+    };
+    let const_name = |field_name: &syn::Ident| {
+        let field_name = field_name.to_string();
+        format_ident!("{}", field_name.trim_start_matches("r#").to_uppercase())
+    };
+
+    let field_set_name = format_ident!("{}FieldSet", original_struct.ident);
+    let field_consts = field_names.iter().enumerate().map(|(bit, field_name)| {
+        let const_name = const_name(field_name);
+        let doc = format!(" The `{field_name}` field");
+        quote! {
+            #[doc = #doc]
+            pub const #const_name: Self = Self(1 << #bit);
+        }
+    });
+
+    let arms = builder.view_structs.iter().map(|view| {
+        let view_name = view.name;
+        let const_names = view.original_fields().map(|e| const_name(e.original_name));
+        quote! {
+            #enum_name::#view_name(_) => #field_set_name::EMPTY #(.union(#field_set_name::#const_names))*
+        }
+    });
+    methods.push(quote! {
+        /// The fields of the original struct held by the view
+        pub fn fields_present(&self) -> #field_set_name {
+            match self {
+                #(#arms,)*
+            }
+        }
+    });
+
+    let vis = &original_struct.vis;
+    Some(quote! {
+        /// A set of the fields of the original struct
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        #vis struct #field_set_name(#bits_type);
+
+        impl #field_set_name {
+            pub const EMPTY: Self = Self(0);
+            #(#field_consts)*
+
+            /// Whether all fields of `other` are in this set
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub const fn union(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl ::core::ops::BitOr for #field_set_name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                self.union(other)
+            }
+        }
+    })
+}
+
 struct CommmonType<'a> {
     stripped_type: &'a syn::Type,
+    getter: &'a syn::Ident,
     is_there_an_option: bool,
     is_there_an_owned: bool,
     is_there_a_ref: bool,
     is_there_a_mut: bool,
+    /// Whether the stripped type is the trait object of a `Box<dyn Trait>`
+    is_boxed: bool,
 }
 
 /// Generate a reference and mutable reference structs
 fn generate_ref_view_structs_and_methods(
     view_struct: &mut ViewStructBuilder,
+    config: &ViewsConfig,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    // todo check this lifetime does not exist
-    let all_owned_fields_additional_immutable_ref = quote! { &'original };
-    let all_owned_fields_additional_mutable_ref = quote! { &'original mut};
+    let ref_method = config.ref_method.clone().unwrap_or_else(|| format_ident!("as_ref"));
+    let mut_method = config.mut_method.clone().unwrap_or_else(|| format_ident!("as_mut"));
+    let original_lifetime = view_struct.original_lifetime.clone();
+    let all_owned_fields_additional_immutable_ref = quote! { &#original_lifetime };
+    let all_owned_fields_additional_mutable_ref = quote! { &#original_lifetime mut};
     let mut uses_additional_lifetime = false;
 
+    let mut all_shared_refs = true;
     let mut immutable_struct_fields = Vec::new();
     let mut mutable_struct_fields = Vec::new();
     let mut immutable_struct_method_fields = Vec::new();
     let mut mutable_struct_method_fields = Vec::new();
     for builder_field in &view_struct.builder_fields {
         let vis = builder_field.vis;
-        let field_name = builder_field.name;
+        let field_name = &builder_field.name;
         let ref_ty = &builder_field.ref_struct_field_type;
         let mut_ty = &builder_field.mut_struct_field_type;
 
+        if builder_field.nested.is_some() {
+            // The `*Ref` and `*Mut` structs of the nested view already borrow for the original lifetime
+            all_shared_refs = false;
+            uses_additional_lifetime = true;
+            immutable_struct_fields.push(quote! {
+                #vis #field_name: #ref_ty
+            });
+            mutable_struct_fields.push(quote! {
+                #vis #field_name: #mut_ty
+            });
+            immutable_struct_method_fields.push(quote! {
+                #field_name: self.#field_name.#ref_method()
+            });
+            mutable_struct_method_fields.push(quote! {
+                #field_name: self.#field_name.#mut_method()
+            });
+            continue;
+        }
+
         // Note: no need to check both, they both will be references or not
         let (additional_immutable_ref, additional_mutable_ref) = match ref_ty {
-            syn::Type::Reference(_) => (None, None),
+            // Overridden with `ref_type`, so only borrowed by the `*Mut` struct
+            _ if builder_field.ref_expr.is_some() => {
+                all_shared_refs &= matches!(ref_ty, syn::Type::Reference(reference) if reference.mutability.is_none());
+                uses_additional_lifetime = true;
+                (None, Some(all_owned_fields_additional_mutable_ref.clone()))
+            }
+            syn::Type::Reference(reference) => {
+                all_shared_refs &= reference.mutability.is_none();
+                (None, None)
+            }
+            // Copied into the `*Ref` struct, so only borrowed by the `*Mut` struct
+            _ if builder_field.copy_in_ref => {
+                uses_additional_lifetime = true;
+                (None, Some(all_owned_fields_additional_mutable_ref.clone()))
+            }
             _ => {
                 uses_additional_lifetime = true;
                 (
@@ -283,9 +1675,26 @@ fn generate_ref_view_structs_and_methods(
         mutable_struct_fields.push(quote! {
             #vis #field_name: #additional_mutable_ref #mut_ty
         });
-        immutable_struct_method_fields.push(quote! {
-            #field_name: &self.#field_name
-        });
+        if let Some(ref_expr) = builder_field.ref_expr {
+            immutable_struct_method_fields.push(quote! {
+                #field_name: {
+                    let #field_name = &self.#field_name;
+                    #ref_expr
+                }
+            });
+        } else if builder_field.deref_in_ref {
+            immutable_struct_method_fields.push(quote! {
+                #field_name: ::core::ops::Deref::deref(&self.#field_name)
+            });
+        } else if builder_field.copy_in_ref {
+            immutable_struct_method_fields.push(quote! {
+                #field_name: self.#field_name
+            });
+        } else {
+            immutable_struct_method_fields.push(quote! {
+                #field_name: &self.#field_name
+            });
+        }
         mutable_struct_method_fields.push(quote! {
             #field_name: &mut self.#field_name
         });
@@ -295,18 +1704,28 @@ fn generate_ref_view_structs_and_methods(
     let mut_struct_name = format_ident!("{}Mut", view_struct.name);
 
     // Add lifetime parameter if does not already exist and needed
-    let (ref_impl_generics, ref_type_generics, ref_where_clause) = if uses_additional_lifetime {
+    if uses_additional_lifetime {
         view_struct.add_original_struct_lifetime_to_refs();
-        let (impl_generics, type_generics, where_clause) = view_struct
-            .get_ref_generics()
-            .expect("If refs use an additional lifetime, then it must have had this generic added")
-            .split_for_impl();
-        (Some(impl_generics), Some(type_generics), Some(where_clause))
-    } else {
-        (None, None, None)
-    };
+    }
+    let (ref_impl_generics, ref_type_generics, ref_where_clause) =
+        if let Some(generics) = view_struct.get_ref_generics() {
+            let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+            (Some(impl_generics), Some(type_generics), Some(where_clause))
+        } else {
+            (None, None, None)
+        };
 
     let ref_attributes = view_struct.ref_attributes;
+    // Shared references are `Copy`, so a `*Ref` struct of only shared references is too
+    let ref_copy_derives = (all_shared_refs && !view_struct.config.no_ref_copy).then(|| {
+        let derives = ["Clone", "Copy"]
+            .into_iter()
+            .filter(|derive| !derives_trait(ref_attributes, derive))
+            .map(|derive| format_ident!("{}", derive))
+            .collect::<Vec<_>>();
+        (!derives.is_empty()).then(|| quote! { #[derive(#(#derives),*)] })
+    })
+    .flatten();
     let mut_attributes = view_struct.mut_attributes;
     let visibility = view_struct.visibility;
 
@@ -318,9 +1737,269 @@ fn generate_ref_view_structs_and_methods(
             (None, None, None)
         };
     let struct_name = &view_struct.name;
+    // A view borrowing from the original struct is borrowed for a shorter lifetime than its own, e.g.
+    // `impl<'original, 'original_view: 'original> Keyed<'original_view>`, else `as_mut` would borrow it for good
+    let (view_impl_generics, view_type_generics) = match view_struct.get_ref_generics() {
+        Some(generics) if view_struct.borrows_original() => {
+            let view_lifetime = syn::Lifetime::new(
+                &format!("{}_view", original_lifetime),
+                original_lifetime.span(),
+            );
+            let mut view_generics = generics.clone();
+            for param in view_generics.params.iter_mut() {
+                if let syn::GenericParam::Lifetime(lifetime_param) = param
+                    && lifetime_param.lifetime == original_lifetime
+                {
+                    lifetime_param.lifetime = view_lifetime.clone();
+                }
+            }
+            let (_, view_type_generics, _) = view_generics.split_for_impl();
+            let view_type_generics = quote! { #view_type_generics };
+            let mut impl_generics = generics.clone();
+            impl_generics
+                .params
+                .insert(1, syn::parse_quote!(#view_lifetime: #original_lifetime));
+            let (view_impl_generics, _, _) = impl_generics.split_for_impl();
+            (quote! { #view_impl_generics }, view_type_generics)
+        }
+        _ => (quote! { #ref_impl_generics }, quote! { #regular_type_generics }),
+    };
+
+    // Downgrade of the `*Mut` struct to the `*Ref` struct, borrowing from the `*Mut` struct
+    let downgrade_fields = view_struct.builder_fields.iter().map(|builder_field| {
+        let field_name = &builder_field.name;
+        if builder_field.nested.is_some() {
+            quote! { #field_name: self.#field_name.downgrade() }
+        } else if let Some(ref_expr) = builder_field.ref_expr {
+            quote! {
+                #field_name: {
+                    let #field_name = &*self.#field_name;
+                    #ref_expr
+                }
+            }
+        } else if builder_field.deref_in_ref {
+            quote! { #field_name: ::core::ops::Deref::deref(&*self.#field_name) }
+        } else if builder_field.copy_in_ref {
+            quote! { #field_name: *self.#field_name }
+        } else {
+            quote! { #field_name: &*self.#field_name }
+        }
+    });
+    // Conversion of the `*Mut` struct into the `*Ref` struct, keeping the borrow of the original
+    let into_ref_fields = view_struct.builder_fields.iter().map(|builder_field| {
+        let field_name = &builder_field.name;
+        if builder_field.nested.is_some() {
+            quote! { #field_name: self.#field_name.into_ref() }
+        } else if let Some(ref_expr) = builder_field.ref_expr {
+            quote! {
+                #field_name: {
+                    let #field_name = &*self.#field_name;
+                    #ref_expr
+                }
+            }
+        } else if builder_field.deref_in_ref {
+            quote! { #field_name: ::core::ops::Deref::deref(&*self.#field_name) }
+        } else if builder_field.copy_in_ref {
+            quote! { #field_name: *self.#field_name }
+        } else if builder_field.is_ref && !builder_field.is_mut {
+            quote! { #field_name: self.#field_name }
+        } else {
+            quote! { #field_name: &*self.#field_name }
+        }
+    });
+    // Reborrow of the `*Mut` struct for a shorter lifetime, shared references of the original are copied
+    let reborrow_fields = view_struct.builder_fields.iter().map(|builder_field| {
+        let field_name = &builder_field.name;
+        if builder_field.nested.is_some() {
+            quote! { #field_name: self.#field_name.reborrow() }
+        } else if builder_field.is_ref && !builder_field.is_mut {
+            quote! { #field_name: self.#field_name }
+        } else {
+            quote! { #field_name: &mut *self.#field_name }
+        }
+    });
+    let downgrade_ty_generics = view_struct.get_ref_generics().map(|generics| {
+        let params = generics.params.iter().map(|param| match param {
+            syn::GenericParam::Lifetime(lifetime_param) if lifetime_param.lifetime == original_lifetime => {
+                quote! { '_ }
+            }
+            syn::GenericParam::Lifetime(lifetime_param) => {
+                let lifetime = &lifetime_param.lifetime;
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Type(type_param) => {
+                let ident = &type_param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Const(const_param) => {
+                let ident = &const_param.ident;
+                quote! { #ident }
+            }
+        });
+        quote! { <#(#params),*> }
+    });
+    // `Deref` to the `*Ref` struct is not possible, since `deref` must return a reference to a `*Ref` struct
+    // stored in the `*Mut` struct, which would borrow from the `*Mut` struct itself
+    let mut mut_methods = vec![quote! {
+        /// Borrows the fields immutably, e.g. to pass this where the `*Ref` struct is expected
+        pub fn downgrade(&self) -> #ref_struct_name #downgrade_ty_generics {
+            #ref_struct_name {
+                #(#downgrade_fields,)*
+            }
+        }
+
+        pub fn #ref_method(&self) -> #ref_struct_name #downgrade_ty_generics {
+            self.downgrade()
+        }
+
+        /// Converts into the `*Ref` struct, borrowing from the original for as long as this did
+        pub fn into_ref(self) -> #ref_struct_name #ref_type_generics {
+            #ref_struct_name {
+                #(#into_ref_fields,)*
+            }
+        }
+
+        /// Reborrows the fields for a shorter lifetime, e.g. to pass this to a function taking the `*Mut`
+        /// struct by value without giving it up
+        pub fn reborrow(&mut self) -> #mut_struct_name #downgrade_ty_generics {
+            #mut_struct_name {
+                #(#reborrow_fields,)*
+            }
+        }
+    }];
+    if view_struct.config.merge_from {
+        let merged_fields = view_struct
+            .builder_fields
+            .iter()
+            .filter(|e| e.is_mergeable())
+            .map(|e| {
+                let field_name = &e.name;
+                if e.copy_in_ref {
+                    quote! { ::core::clone::Clone::clone_from(&mut *self.#field_name, &other.#field_name); }
+                } else {
+                    quote! { ::core::clone::Clone::clone_from(&mut *self.#field_name, other.#field_name); }
+                }
+            });
+        mut_methods.push(quote! {
+            /// Clones the owned fields of `other` into the fields borrowed by this view
+            pub fn merge_from(&mut self, other: &#ref_struct_name #downgrade_ty_generics) {
+                #(#merged_fields)*
+            }
+        });
+    }
+    for builder_field in &view_struct.builder_fields {
+        let field_name = &builder_field.name;
+        if let Some((method_name, element_type)) = &builder_field.push_method {
+            mut_methods.push(quote! {
+                pub fn #method_name(&mut self, value: #element_type) {
+                    self.#field_name.push(value);
+                }
+            });
+        }
+        if let Some(inner_type) = &builder_field.owned_option_inner_type {
+            let take_method = format_ident!("take_{}", field_name);
+            let replace_method = format_ident!("replace_{}", field_name);
+            mut_methods.push(quote! {
+                pub fn #take_method(&mut self) -> Option<#inner_type> {
+                    self.#field_name.take()
+                }
+
+                pub fn #replace_method(&mut self, value: #inner_type) -> Option<#inner_type> {
+                    self.#field_name.replace(value)
+                }
+            });
+        }
+    }
+    let mut_methods_impl = quote! {
+        impl #ref_impl_generics #mut_struct_name #ref_type_generics #ref_where_clause {
+            #(#mut_methods)*
+        }
+    };
+
+    let cow_mut_struct = view_struct.config.cow_mut.then(|| {
+        let cow_mut_name = format_ident!("{}CowMut", view_struct.name);
+        let mut cow_fields = Vec::new();
+        let mut to_mut_methods = Vec::new();
+        for builder_field in &view_struct.builder_fields {
+            let vis = builder_field.vis;
+            let field_name = &builder_field.name;
+            let ref_ty = &builder_field.ref_struct_field_type;
+            cow_fields.push(quote! {
+                #vis #field_name: ::std::borrow::Cow<#original_lifetime, #ref_ty>
+            });
+            let to_mut_method = format_ident!("to_mut_{}", field_name);
+            to_mut_methods.push(quote! {
+                /// Mutable access to the field, cloning it on first access if still borrowed
+                pub fn #to_mut_method(&mut self) -> &mut <#ref_ty as ::std::borrow::ToOwned>::Owned {
+                    self.#field_name.to_mut()
+                }
+            });
+        }
+        quote! {
+            /// The fields of the view, borrowed from the original until first mutated
+            #visibility struct #cow_mut_name #ref_type_generics #ref_where_clause {
+                #(#cow_fields,)*
+            }
+
+            impl #ref_impl_generics #cow_mut_name #ref_type_generics #ref_where_clause {
+                #(#to_mut_methods)*
+            }
+        }
+    });
+
+    // Clones the view out of the `*Ref` struct, unless a field can not be rebuilt from its `*Ref` field, e.g.
+    // a `&mut` or `str_ref` field. The `Clone` bounds are higher-ranked, so they are only checked where the
+    // method is called, rather than failing to compile for fields that are not `Clone`.
+    let can_clone_owned = view_struct.builder_fields.iter().all(|e| {
+        e.nested.is_none()
+            && !e.is_mut
+            && !e.deref_in_ref
+            && e.ref_expr.is_none()
+            && !matches!(e.ref_struct_field_type, syn::Type::Slice(_))
+    });
+    let to_owned_view_impl = can_clone_owned.then(|| {
+        let mut clone_bounds = Vec::new();
+        let assignments = view_struct.builder_fields.iter().map(|builder_field| {
+            let field_name = &builder_field.name;
+            if builder_field.is_ref || builder_field.copy_in_ref {
+                quote! { #field_name: self.#field_name }
+            } else {
+                let ty = &builder_field.regular_struct_field_type;
+                clone_bounds.push(quote! { for<'clone> #ty: ::core::clone::Clone });
+                quote! { #field_name: ::core::clone::Clone::clone(self.#field_name) }
+            }
+        }).collect::<Vec<_>>();
+        quote! {
+            impl #ref_impl_generics #ref_struct_name #ref_type_generics #ref_where_clause {
+                /// Clones the fields into the owned view
+                pub fn to_owned_view(&self) -> #struct_name #regular_type_generics
+                where
+                    #(#clone_bounds,)*
+                {
+                    #struct_name {
+                        #(#assignments,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let ref_validate_impls = generate_validate_method(view_struct, ViewKind::Ref, &ref_method).map(|ref_validate| {
+        let mut_validate = generate_validate_method(view_struct, ViewKind::Mut, &ref_method);
+        quote! {
+            impl #ref_impl_generics #ref_struct_name #ref_type_generics #ref_where_clause {
+                #ref_validate
+            }
+
+            impl #ref_impl_generics #mut_struct_name #ref_type_generics #ref_where_clause {
+                #mut_validate
+            }
+        }
+    });
 
     Ok(quote! {
         #(#ref_attributes)*
+        #ref_copy_derives
         #visibility struct #ref_struct_name #ref_type_generics #ref_where_clause {
             #(#immutable_struct_fields,)*
         }
@@ -330,19 +2009,27 @@ fn generate_ref_view_structs_and_methods(
             #(#mutable_struct_fields,)*
         }
 
-        impl #ref_impl_generics #struct_name #regular_type_generics #regular_where_clause {
-            pub fn as_ref(&'original self) -> #ref_struct_name #ref_type_generics {
+        impl #view_impl_generics #struct_name #view_type_generics #regular_where_clause {
+            pub fn #ref_method(&#original_lifetime self) -> #ref_struct_name #ref_type_generics {
                 #ref_struct_name {
                     #(#immutable_struct_method_fields,)*
                 }
             }
 
-            pub fn as_mut(&'original mut self) -> #mut_struct_name #ref_type_generics {
+            pub fn #mut_method(&#original_lifetime mut self) -> #mut_struct_name #ref_type_generics {
                 #mut_struct_name {
                     #(#mutable_struct_method_fields,)*
                 }
             }
         }
+
+        #ref_validate_impls
+
+        #to_owned_view_impl
+
+        #mut_methods_impl
+
+        #cow_mut_struct
     })
 }
 
@@ -353,14 +2040,18 @@ fn generate_original_conversion_methods(
 ) -> syn::Result<proc_macro2::TokenStream> {
     let original_name = &original_struct.ident;
     let original_generics = &original_struct.generics;
-    let (_, original_ty_generics, original_where_clause) = original_generics.split_for_impl();
-    let mut generics_with_new_lifetime = original_generics.clone();
-    generics_with_new_lifetime
-        .params
-        .insert(0, syn::parse_quote!('original));
-    let (impl_generics, _, _) = generics_with_new_lifetime.split_for_impl();
+    let (impl_generics, original_ty_generics, original_where_clause) = original_generics.split_for_impl();
+    // The borrow lifetime is declared on each borrowing method, so callers can name it
+    let original_lifetime = &context.original_lifetime;
 
     let mut methods = Vec::new();
+    let mut trait_impls = Vec::new();
+
+    // The original struct borrowed mutably, e.g. for `TryFrom<&'original mut Search<'a>>`
+    let mut borrowed_generics = original_generics.clone();
+    borrowed_generics.params.insert(0, syn::parse_quote!(#original_lifetime));
+    let (borrowed_impl_generics, _, _) = borrowed_generics.split_for_impl();
+    let borrowed_original = quote! { &#original_lifetime mut #original_name #original_ty_generics };
 
     for view_struct in &context.view_structs {
         let view_name = view_struct.name;
@@ -371,18 +2062,17 @@ fn generate_original_conversion_methods(
         let as_mut_method = format_ident!("as_{}_mut", snake_case_name);
 
         // Generate field assignments
-        let into_assignments = generate_into_assignments(&view_struct.builder_fields)?;
+        let into_assignments = generate_into_assignments(&view_struct.builder_fields, false)?;
         let ref_assignments = generate_ref_assignments(&view_struct.builder_fields)?;
-        let mut_assignments = generate_mut_assignments(&view_struct.builder_fields)?;
+        let mut_assignments = generate_mut_assignments(&view_struct.builder_fields, &quote! { self }, false)?;
 
         // Determine return types
-        let view_generics = view_struct.get_regular_generics();
+        let view_generics = view_struct.get_regular_generics().map(|e| {
+            let (_, ty_generics, _) = e.split_for_impl();
+            ty_generics
+        });
 
-        // Check if any field requires unwrapping (pattern matching)
-        let has_unwrapping = view_struct
-            .builder_fields
-            .iter()
-            .any(|e| e.pattern_to_match.is_some() || e.validation.is_some());
+        let has_unwrapping = view_struct.is_fallible();
         let into_return_type = if has_unwrapping {
             quote! { Option<#view_name #view_generics> }
         } else {
@@ -397,125 +2087,727 @@ fn generate_original_conversion_methods(
             type_generics
         });
 
-        let ref_return_type = if has_unwrapping {
-            quote! { Option<#ref_struct_name # ref_struct_generics> }
-        } else {
-            quote! { #ref_struct_name #ref_struct_generics }
-        };
+        let ref_return_type = if has_unwrapping {
+            quote! { Option<#ref_struct_name # ref_struct_generics> }
+        } else {
+            quote! { #ref_struct_name #ref_struct_generics }
+        };
+
+        let uses_mut_guard = uses_mut_guard(view_struct, context.config);
+        let mut_guard_name = format_ident!("{}MutGuard", view_name);
+        let mut_return_type = if uses_mut_guard {
+            quote! { Option<#mut_guard_name #ref_struct_generics> }
+        } else if has_unwrapping {
+            quote! { Option<#mut_struct_name #ref_struct_generics> }
+        } else {
+            quote! { #mut_struct_name #ref_struct_generics }
+        };
+
+        // Method bodies
+        let into_body = if view_struct.validation.is_some() {
+            quote! {
+                let view = #view_name {
+                    #(#into_assignments,)*
+                };
+                if !view.validate() {
+                    return None;
+                }
+                Some(view)
+            }
+        } else if has_unwrapping {
+            quote! {
+                Some(#view_name {
+                    #(#into_assignments,)*
+                })
+            }
+        } else {
+            quote! {
+                #view_name {
+                    #(#into_assignments,)*
+                }
+            }
+        };
+
+        let ref_body = if view_struct.validation.is_some() {
+            quote! {
+                let view = #ref_struct_name {
+                    #(#ref_assignments,)*
+                };
+                if !view.validate() {
+                    return None;
+                }
+                Some(view)
+            }
+        } else if has_unwrapping {
+            quote! {
+                Some(#ref_struct_name {
+                    #(#ref_assignments,)*
+                })
+            }
+        } else {
+            quote! {
+                #ref_struct_name {
+                    #(#ref_assignments,)*
+                }
+            }
+        };
+
+        let mut_body = if uses_mut_guard {
+            let view_validation = view_struct.validation.as_ref().map(|_| {
+                quote! {
+                    if !view.validate() {
+                        return None;
+                    }
+                }
+            });
+            quote! {
+                let view = #mut_struct_name {
+                    #(#mut_assignments,)*
+                };
+                #view_validation
+                Some(#mut_guard_name(view))
+            }
+        } else if view_struct.validation.is_some() {
+            quote! {
+                let view = #mut_struct_name {
+                    #(#mut_assignments,)*
+                };
+                if !view.validate() {
+                    return None;
+                }
+                Some(view)
+            }
+        } else if has_unwrapping {
+            quote! {
+                Some(#mut_struct_name {
+                    #(#mut_assignments,)*
+                })
+            }
+        } else {
+            quote! {
+                #mut_struct_name {
+                    #(#mut_assignments,)*
+                }
+            }
+        };
+
+        // Like the `as_*_mut` method, with the name of the first failing field as the error, like `into_*_reported`
+        if has_unwrapping {
+            let reported_assignments =
+                generate_mut_assignments(&view_struct.builder_fields, &quote! { original }, true)?;
+            let view_validation = view_struct.validation.as_ref().map(|_| {
+                quote! {
+                    if !view.validate() {
+                        return Err("valid");
+                    }
+                }
+            });
+            let (target, view) = if uses_mut_guard {
+                (quote! { #mut_guard_name }, quote! { #mut_guard_name(view) })
+            } else {
+                (quote! { #mut_struct_name }, quote! { view })
+            };
+            trait_impls.push(quote! {
+                impl #borrowed_impl_generics ::core::convert::TryFrom<#borrowed_original> for #target #ref_struct_generics #original_where_clause {
+                    type Error = &'static str;
+
+                    fn try_from(original: #borrowed_original) -> Result<Self, Self::Error> {
+                        let view = #mut_struct_name {
+                            #(#reported_assignments,)*
+                        };
+                        #view_validation
+                        Ok(#view)
+                    }
+                }
+            });
+        } else {
+            let borrowed_assignments =
+                generate_mut_assignments(&view_struct.builder_fields, &quote! { original }, false)?;
+            trait_impls.push(quote! {
+                impl #borrowed_impl_generics ::core::convert::From<#borrowed_original> for #mut_struct_name #ref_struct_generics #original_where_clause {
+                    fn from(original: #borrowed_original) -> Self {
+                        #mut_struct_name {
+                            #(#borrowed_assignments,)*
+                        }
+                    }
+                }
+            });
+        }
+
+        // A view borrowing fields is built from a borrow of the original, its other fields copied out of it
+        let into_receiver = if !view_struct.borrows_original() {
+            quote! { (self) }
+        } else if view_struct.builder_fields.iter().any(|e| e.is_borrowed && e.is_mut) {
+            quote! { <#original_lifetime>(&#original_lifetime mut self) }
+        } else {
+            quote! { <#original_lifetime>(&#original_lifetime self) }
+        };
+
+        methods.push(quote! {
+            pub fn #into_method #into_receiver -> #into_return_type {
+                #into_body
+            }
+
+            pub fn #as_ref_method<#original_lifetime>(&#original_lifetime self) -> #ref_return_type {
+                #ref_body
+            }
+
+            pub fn #as_mut_method<#original_lifetime>(&#original_lifetime mut self) -> #mut_return_type {
+                #mut_body
+            }
+        });
+
+        // The `*Ref` struct alongside a mutable reference to a field outside the view
+        for and_mut_field in &view_struct.config.and_mut {
+            let and_mut_method = format_ident!("{}_ref_and_{}_mut", snake_case_name, and_mut_field);
+            let and_mut_ty = &original_struct
+                .fields
+                .iter()
+                .zip(original_field_names(original_struct))
+                .find(|(_, name)| name == and_mut_field)
+                .expect("Validated in resolve")
+                .0
+                .ty;
+            let (and_mut_return_type, and_mut_result) = if has_unwrapping {
+                (
+                    quote! { Option<(#ref_struct_name #ref_struct_generics, &#original_lifetime mut #and_mut_ty)> },
+                    quote! { Some((view?, field)) },
+                )
+            } else {
+                (
+                    quote! { (#ref_struct_name #ref_struct_generics, &#original_lifetime mut #and_mut_ty) },
+                    quote! { (view, field) },
+                )
+            };
+            methods.push(quote! {
+                pub fn #and_mut_method<#original_lifetime>(&#original_lifetime mut self) -> #and_mut_return_type {
+                    let field = &mut self.#and_mut_field;
+                    let view = {
+                        #ref_body
+                    };
+                    #and_mut_result
+                }
+            });
+        }
+
+        if view_struct.config.cow_mut {
+            let cow_mut_method = format_ident!("as_{}_cow_mut", snake_case_name);
+            let cow_mut_name = format_ident!("{}CowMut", view_name);
+            let cow_assignments = view_struct.builder_fields.iter().map(|e| {
+                let field_name = &e.name;
+                if e.copy_in_ref {
+                    quote! { #field_name: ::std::borrow::Cow::Owned(view.#field_name) }
+                } else {
+                    quote! { #field_name: ::std::borrow::Cow::Borrowed(view.#field_name) }
+                }
+            });
+            let (cow_mut_return_type, view, cow_mut) = if has_unwrapping {
+                (
+                    quote! { Option<#cow_mut_name #ref_struct_generics> },
+                    quote! { self.#as_ref_method()? },
+                    quote! { Some(#cow_mut_name { #(#cow_assignments,)* }) },
+                )
+            } else {
+                (
+                    quote! { #cow_mut_name #ref_struct_generics },
+                    quote! { self.#as_ref_method() },
+                    quote! { #cow_mut_name { #(#cow_assignments,)* } },
+                )
+            };
+            methods.push(quote! {
+                /// Borrows the fields of the view, which are cloned when first mutated
+                pub fn #cow_mut_method<#original_lifetime>(&#original_lifetime self) -> #cow_mut_return_type {
+                    let view = #view;
+                    #cow_mut
+                }
+            });
+        }
+
+        if view_struct.config.snapshot {
+            let snapshot_method = format_ident!("{}_snapshot", snake_case_name);
+            let snapshot_name = format_ident!("{}Snapshot", view_name);
+            let snapshot_assignments = view_struct.builder_fields.iter().map(|e| {
+                let field_name = &e.name;
+                if e.copy_in_ref {
+                    quote! { #field_name: view.#field_name }
+                } else {
+                    quote! { #field_name: *view.#field_name }
+                }
+            });
+            let (snapshot_return_type, view, snapshot) = if has_unwrapping {
+                (
+                    quote! { Option<#snapshot_name #view_generics> },
+                    quote! { self.#as_ref_method()? },
+                    quote! { Some(#snapshot_name { #(#snapshot_assignments,)* }) },
+                )
+            } else {
+                (
+                    quote! { #snapshot_name #view_generics },
+                    quote! { self.#as_ref_method() },
+                    quote! { #snapshot_name { #(#snapshot_assignments,)* } },
+                )
+            };
+            methods.push(quote! {
+                /// Copies the fields of the view without consuming the original
+                pub fn #snapshot_method(&self) -> #snapshot_return_type {
+                    let view = #view;
+                    #snapshot
+                }
+            });
+        }
+
+        if view_struct.config.take {
+            let take_method = format_ident!("take_{}", snake_case_name);
+            let take_assignments = view_struct.builder_fields.iter().map(|e| {
+                let field_name = &e.name;
+                let source = &e.source;
+                match e.pattern_to_match {
+                    Some(pattern_path) => quote! {
+                        #field_name: if let #pattern_path(#field_name) = ::core::mem::take(&mut self.#source) {
+                            #field_name
+                        } else {
+                            unreachable!()
+                        }
+                    },
+                    None => quote! { #field_name: ::core::mem::take(&mut self.#source) },
+                }
+            });
+            let (take_return_type, take_body) = if has_unwrapping {
+                (
+                    quote! { Option<#view_name #view_generics> },
+                    quote! {
+                        // Nothing is taken unless the patterns and validations hold
+                        if self.#as_ref_method().is_none() {
+                            return None;
+                        }
+                        Some(#view_name { #(#take_assignments,)* })
+                    },
+                )
+            } else {
+                (
+                    quote! { #view_name #view_generics },
+                    quote! { #view_name { #(#take_assignments,)* } },
+                )
+            };
+            methods.push(quote! {
+                /// Moves the fields of the view out of the original, leaving their `Default` in place
+                pub fn #take_method(&mut self) -> #take_return_type {
+                    #take_body
+                }
+            });
+        }
 
-        let mut_return_type = if has_unwrapping {
-            quote! { Option<#mut_struct_name #ref_struct_generics> }
-        } else {
-            quote! { #mut_struct_name #ref_struct_generics }
-        };
+        if has_unwrapping {
+            let reported_method = format_ident!("into_{}_reported", snake_case_name);
+            let reported_assignments = generate_into_assignments(&view_struct.builder_fields, true)?;
+            let view_validation = view_struct.validation.as_ref().map(|_| {
+                quote! {
+                    if !view.validate() {
+                        return (None, Some("valid"));
+                    }
+                }
+            });
+            methods.push(quote! {
+                /// Like the `into_*` conversion, but on failure also returns the name of the first field whose
+                /// pattern or validation does not hold, or `"valid"` for the view's `where valid` validation
+                pub fn #reported_method #into_receiver -> (Option<#view_name #view_generics>, Option<&'static str>) {
+                    let view = #view_name {
+                        #(#reported_assignments,)*
+                    };
+                    #view_validation
+                    (Some(view), None)
+                }
+            });
 
-        // Method bodies
-        let into_body = if has_unwrapping {
-            quote! {
-                Some(#view_name {
-                    #(#into_assignments,)*
+            let validate_all_method = format_ident!("validate_all_{}", snake_case_name);
+            let field_checks = view_struct.builder_fields.iter().map(|builder_field| {
+                let Some(nested) = &builder_field.nested else {
+                    return generate_field_failure(builder_field);
+                };
+                let nested_checks = nested.builder_fields.iter().map(generate_field_failure);
+                // The validation of a nested fragment needs its fields, so only runs once they hold
+                if nested.validation.is_none() {
+                    return Some(quote! { #(#nested_checks)* });
+                }
+                let nested_as_ref_method = format_ident!("as_{}", pascal_to_snake_case(&nested.name.to_string()));
+                let field_name = builder_field.name.to_string();
+                Some(quote! {
+                    let nested_failures = failures.len();
+                    #(#nested_checks)*
+                    if failures.len() == nested_failures && self.#nested_as_ref_method().is_none() {
+                        failures.push(#field_name);
+                    }
                 })
+            });
+            let view_validation = view_struct.validation.as_ref().map(|_| {
+                quote! {
+                    if failures.is_empty() && self.#as_ref_method().is_none() {
+                        failures.push("valid");
+                    }
+                }
+            });
+            methods.push(quote! {
+                /// Like the `into_*` conversion, but rather than stopping at the first failure, checks every pattern
+                /// and validation of the view, returning the names of all fields that do not hold. The view's
+                /// `where valid` validation needs every field, so `"valid"` is only returned if they all hold.
+                #[allow(clippy::redundant_pattern_matching)]
+                pub fn #validate_all_method(&self) -> Result<(), Vec<&'static str>> {
+                    let mut failures = Vec::new();
+                    #(#field_checks)*
+                    #view_validation
+                    if failures.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(failures)
+                    }
+                }
+            });
+
+            let expect_method = format_ident!("expect_{}", snake_case_name);
+            let panic_message = format!(
+                "`{original_name}` does not satisfy the patterns and validations of `{view_name}`"
+            );
+            methods.push(quote! {
+                /// Like the `into_*` conversion, but panics if the view's patterns or validations do not hold
+                #[track_caller]
+                pub fn #expect_method #into_receiver -> #view_name #view_generics {
+                    match self.#into_method() {
+                        Some(view) => view,
+                        None => panic!(#panic_message),
+                    }
+                }
+            });
+        }
+    }
+
+    let kind_name = format_ident!("{}VariantKind", original_name);
+    // Each distinct field check shared by views is evaluated once, before the views are checked
+    let mut cached_checks: Vec<(String, syn::Ident, proc_macro2::TokenStream)> = Vec::new();
+    let satisfied_checks = context.view_structs.iter().map(|view_struct| {
+        let view_name = view_struct.name;
+        if context.config.cache_validations && view_struct.is_fallible() {
+            let mut check_names = Vec::new();
+            for field in view_struct.original_fields() {
+                let Some(check) = generate_field_check(field) else {
+                    continue;
+                };
+                let key = check.to_string();
+                let check_name = match cached_checks.iter().find(|(e, _, _)| *e == key) {
+                    Some((_, check_name, _)) => check_name.clone(),
+                    None => {
+                        let check_name = format_ident!("check_{}", cached_checks.len());
+                        cached_checks.push((key, check_name.clone(), check));
+                        check_name
+                    }
+                };
+                check_names.push(check_name);
             }
-        } else {
+            // View-level and nested validations need the fields of the view
+            let view_check = (view_struct.validation.is_some()
+                || view_struct.builder_fields.iter().any(|e| e.nested.is_some()))
+            .then(|| {
+                let as_ref_method = format_ident!("as_{}", pascal_to_snake_case(&view_name.to_string()));
+                quote! { && self.#as_ref_method().is_some() }
+            });
             quote! {
-                #view_name {
-                    #(#into_assignments,)*
+                if true #(&& #check_names)* #view_check {
+                    kinds.push(#kind_name::#view_name);
                 }
             }
-        };
-
-        let ref_body = if has_unwrapping {
+        } else if view_struct.is_fallible() {
+            let as_ref_method = format_ident!("as_{}", pascal_to_snake_case(&view_name.to_string()));
             quote! {
-                Some(#ref_struct_name {
-                    #(#ref_assignments,)*
-                })
+                if self.#as_ref_method().is_some() {
+                    kinds.push(#kind_name::#view_name);
+                }
             }
         } else {
             quote! {
-                #ref_struct_name {
-                    #(#ref_assignments,)*
-                }
+                kinds.push(#kind_name::#view_name);
             }
-        };
+        }
+    });
+    let satisfied_checks = satisfied_checks.collect::<Vec<_>>();
+    let cached_checks = cached_checks.iter().map(|(_, check_name, check)| {
+        quote! { let #check_name = #check; }
+    });
+    methods.push(quote! {
+        /// The kinds of views whose patterns and validations hold for this
+        #[allow(clippy::redundant_pattern_matching)]
+        pub fn satisfied_views(&self) -> Vec<#kind_name> {
+            #(#cached_checks)*
+            let mut kinds = Vec::new();
+            #(#satisfied_checks)*
+            kinds
+        }
+    });
 
-        let mut_body = if has_unwrapping {
-            quote! {
-                Some(#mut_struct_name {
-                    #(#mut_assignments,)*
-                })
-            }
-        } else {
+    Ok(quote! {
+        #[allow(clippy::question_mark)]
+        impl #impl_generics #original_name #original_ty_generics #original_where_clause {
+            #(#methods)*
+        }
+
+        #(#trait_impls)*
+    })
+}
+
+/// Generate `From<View> for Original` for each view covering every field of the original struct, moving the
+/// fields back, including those of nested views
+fn generate_view_to_original(original_struct: &ItemStruct, builder: &Builder) -> Vec<proc_macro2::TokenStream> {
+    let original_name = &original_struct.ident;
+    let (impl_generics, ty_generics, where_clause) = original_struct.generics.split_for_impl();
+    builder
+        .view_structs
+        .iter()
+        .filter(|view_struct| view_struct.covers_original(original_struct))
+        .map(|view_struct| {
+            let view_name = view_struct.name;
+            let view_ty_generics = view_struct.get_regular_generics().map(|e| {
+                let (_, ty_generics, _) = e.split_for_impl();
+                ty_generics
+            });
+            let assignments = view_struct.builder_fields.iter().flat_map(|builder_field| match &builder_field.nested {
+                Some(nested) => {
+                    let nested_name = &builder_field.name;
+                    nested
+                        .builder_fields
+                        .iter()
+                        .map(|e| {
+                            let field_name = &e.name;
+                            let source = &e.source;
+                            quote! { #source: view.#nested_name.#field_name }
+                        })
+                        .collect::<Vec<_>>()
+                }
+                None => {
+                    let field_name = &builder_field.name;
+                    let source = &builder_field.source;
+                    vec![quote! { #source: view.#field_name }]
+                }
+            });
             quote! {
-                #mut_struct_name {
-                    #(#mut_assignments,)*
+                impl #impl_generics ::core::convert::From<#view_name #view_ty_generics> for #original_name #ty_generics #where_clause {
+                    fn from(view: #view_name #view_ty_generics) -> Self {
+                        Self {
+                            #(#assignments,)*
+                        }
+                    }
                 }
             }
-        };
+        })
+        .collect()
+}
 
-        methods.push(quote! {
-            pub fn #into_method(self) -> #into_return_type {
-                #into_body
+/// Generate the `VIEW_PLAN` const of the `debug_plan` option, a summary of each resolved view with its
+/// fields, their fragment, pattern, and validation, and the types of the `*Ref` and `*Mut` fields
+fn generate_view_plan(original_struct: &ItemStruct, builder: &Builder) -> proc_macro2::TokenStream {
+    fn push_fields(lines: &mut Vec<String>, builder_fields: &[BuilderViewField], builder: &Builder, indent: &str) {
+        let original_lifetime = &builder.original_lifetime;
+        for builder_field in builder_fields {
+            let mut line = format!(
+                "{indent}{}: {}",
+                builder_field.name,
+                type_to_string(&builder_field.regular_struct_field_type)
+            );
+            if let Some(fragment) = builder_field.fragment {
+                line.push_str(&format!(", from frag {fragment}"));
+            }
+            if let Some(pattern) = builder_field.pattern_to_match {
+                line.push_str(&format!(", matching {}", type_to_string(pattern)));
+            }
+            if let Some(validation) = builder_field.validation {
+                line.push_str(&format!(", if {}", type_to_string(validation)));
             }
+            lines.push(line);
+            if let Some(nested) = &builder_field.nested {
+                push_fields(lines, &nested.builder_fields, builder, &format!("{indent}    "));
+                continue;
+            }
+            // As emitted by `generate_ref_view_structs_and_methods`
+            let ref_ty = &builder_field.ref_struct_field_type;
+            let mut_ty = &builder_field.mut_struct_field_type;
+            let (ref_ty, mut_ty) = match ref_ty {
+                _ if builder_field.ref_expr.is_some() => (quote! { #ref_ty }, quote! { &#original_lifetime mut #mut_ty }),
+                syn::Type::Reference(_) => (quote! { #ref_ty }, quote! { #mut_ty }),
+                _ if builder_field.copy_in_ref => (quote! { #ref_ty }, quote! { &#original_lifetime mut #mut_ty }),
+                _ => (
+                    quote! { &#original_lifetime #ref_ty },
+                    quote! { &#original_lifetime mut #mut_ty },
+                ),
+            };
+            lines.push(format!(
+                "{indent}    Ref: {}, Mut: {}",
+                type_to_string(&ref_ty),
+                type_to_string(&mut_ty)
+            ));
+        }
+    }
 
-            pub fn #as_ref_method(&'original self) -> #ref_return_type {
-                #ref_body
+    let mut lines = Vec::new();
+    for view_struct in &builder.view_structs {
+        let generics = view_struct
+            .get_regular_generics()
+            .map(type_to_string)
+            .unwrap_or_default();
+        let fallibility = if view_struct.is_fallible() { "fallible" } else { "infallible" };
+        lines.push(format!("view {}{generics}, {fallibility}", view_struct.name));
+        push_fields(&mut lines, &view_struct.builder_fields, builder, "    ");
+        if let Some(validation) = &view_struct.validation {
+            lines.push(format!("    where {}", type_to_string(validation)));
+        }
+    }
+    let plan = lines.join("\n");
+    let original_name = &original_struct.ident;
+    let (impl_generics, ty_generics, where_clause) = original_struct.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #original_name #ty_generics #where_clause {
+            /// The resolved views, generated by `debug_plan`
+            pub const VIEW_PLAN: &'static str = #plan;
+        }
+    }
+}
+
+/// Generate static checks of the types of the view fields that resolve can not check, e.g. that fields copied
+/// into `*Ref` structs with `skip_ref` are `Copy`, reported at the field
+fn generate_field_assertions(original_struct: &ItemStruct, builder: &Builder) -> Option<proc_macro2::TokenStream> {
+    let original_name = &original_struct.ident;
+    let (impl_generics, ty_generics, where_clause) = original_struct.generics.split_for_impl();
+    let copy_assertions = builder
+        .view_structs
+        .iter()
+        .flat_map(|e| e.original_fields())
+        .filter(|e| e.copy_in_ref)
+        .map(|e| {
+            let ty = &e.regular_struct_field_type;
+            quote_spanned! { e.name.span()=> let _ = assert_copy::<#ty>; }
+        })
+        .collect::<Vec<_>>();
+    if copy_assertions.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn assert_view_fields #impl_generics (_original: &#original_name #ty_generics) #where_clause {
+                fn assert_copy<T: ::core::marker::Copy>() {}
+                #(#copy_assertions)*
             }
+        };
+    })
+}
 
-            pub fn #as_mut_method(&'original mut self) -> #mut_return_type {
-                #mut_body
+/// Generate whether the pattern and validation of a field hold for the original struct, if it has either
+fn generate_field_check(builder_field: &BuilderViewField) -> Option<proc_macro2::TokenStream> {
+    let field_name = &builder_field.name;
+    let source = &builder_field.source;
+    match (builder_field.pattern_to_match, builder_field.validation) {
+        (Some(pattern_path), Some(validation)) => Some(quote! {
+            if let #pattern_path(#field_name) = &self.#source { #validation } else { false }
+        }),
+        (Some(pattern_path), None) => Some(quote! {
+            matches!(&self.#source, #pattern_path(_))
+        }),
+        (None, Some(validation)) => Some(quote! {
+            {
+                let #field_name = &self.#source;
+                #validation
             }
-        });
+        }),
+        (None, None) => None,
     }
+}
 
-    Ok(quote! {
-        impl #impl_generics #original_name #original_ty_generics #original_where_clause {
-            #(#methods)*
+/// Generate a push of the field's name onto `failures` if its pattern or validation does not hold
+fn generate_field_failure(builder_field: &BuilderViewField) -> Option<proc_macro2::TokenStream> {
+    let check = generate_field_check(builder_field)?;
+    let field_name = builder_field.name.to_string();
+    Some(quote! {
+        if !(#check) {
+            failures.push(#field_name);
         }
     })
 }
 
+/// Generate field assignments for into methods. With `report_failures`, a failing field returns
+/// `(None, Some(field_name))` rather than `None`.
+///
+/// The assignments go in a struct expression, whose fields are evaluated in the order written. A failing field
+/// returns before the struct is built, so the patterns and validations of later fields never run.
 fn generate_into_assignments(
     builder_fields: &[BuilderViewField],
+    report_failures: bool,
 ) -> syn::Result<Vec<proc_macro2::TokenStream>> {
     let mut assignments = Vec::new();
 
     for builder_field in builder_fields {
-        let field_name = builder_field.name;
+        let field_name = &builder_field.name;
+        let source = &builder_field.source;
+        let value = match (builder_field.is_borrowed, builder_field.is_mut) {
+            (true, true) => quote! { &mut self.#source },
+            (true, false) => quote! { &self.#source },
+            (false, _) => quote! { self.#source },
+        };
+        let fail = if report_failures {
+            let field_name = field_name.to_string();
+            quote! { return (None, Some(#field_name)) }
+        } else {
+            quote! { return None }
+        };
 
-        let assignment = if let Some(pattern_path) = builder_field.pattern_to_match {
+        let assignment = if let Some(nested) = &builder_field.nested {
+            let nested_name = &nested.name;
+            let nested_assignments = generate_into_assignments(&nested.builder_fields, report_failures)?;
+            let nested_validation = nested.validation.as_ref().map(|_| {
+                quote! {
+                    if !view.validate() {
+                        #fail;
+                    }
+                }
+            });
+            quote! {
+                #field_name: {
+                    let view = #nested_name {
+                        #(#nested_assignments,)*
+                    };
+                    #nested_validation
+                    view
+                }
+            }
+        } else if let Some(pattern_path) = builder_field.pattern_to_match {
             if let Some(validation) = builder_field.validation {
                 quote! {
-                    #field_name: if let #pattern_path(#field_name) = self.#field_name {
+                    #field_name: if let #pattern_path(#field_name) = #value {
                         {
                             let #field_name = &#field_name;
                             if !(#validation) {
-                                return None;
+                                #fail;
                             }
                         }
                         #field_name
                     } else {
-                        return None;
+                        #fail;
                     }
                 }
             } else {
                 quote! {
-                    #field_name: if let #pattern_path(#field_name) = self.#field_name { #field_name } else { return None }
+                    #field_name: if let #pattern_path(#field_name) = #value { #field_name } else { #fail }
                 }
             }
         } else {
             if let Some(validation) = builder_field.validation {
                 quote! {
                     #field_name: {
-                        let #field_name = &self.#field_name;
+                        let #field_name = &self.#source;
                         if !(#validation) {
-                            return None;
+                            #fail;
                         }
-                        self.#field_name
+                        #value
                     }
                 }
             } else {
                 quote! {
-                    #field_name: self.#field_name
+                    #field_name: #value
                 }
             }
         };
@@ -526,19 +2818,40 @@ fn generate_into_assignments(
     Ok(assignments)
 }
 
+/// Generate field assignments for as_ref methods, which short-circuit like [`generate_into_assignments`]
 fn generate_ref_assignments(
     builder_fields: &[BuilderViewField],
 ) -> syn::Result<Vec<proc_macro2::TokenStream>> {
     let mut assignments = Vec::new();
 
     for builder_field in builder_fields {
-        let field_name = builder_field.name;
+        let field_name = &builder_field.name;
+        let source = &builder_field.source;
 
-        let assignment = if let Some(pattern_path) = builder_field.pattern_to_match {
+        let value = if let Some(nested) = &builder_field.nested {
+            let nested_ref_name = format_ident!("{}Ref", nested.name);
+            let nested_assignments = generate_ref_assignments(&nested.builder_fields)?;
+            let nested_validation = nested.validation.as_ref().map(|_| {
+                quote! {
+                    if !view.validate() {
+                        return None;
+                    }
+                }
+            });
+            quote! {
+                {
+                    let view = #nested_ref_name {
+                        #(#nested_assignments,)*
+                    };
+                    #nested_validation
+                    view
+                }
+            }
+        } else if let Some(pattern_path) = builder_field.pattern_to_match {
             // Generate explicit pattern matching for references
             if let Some(validation) = builder_field.validation {
                 quote! {
-                    #field_name: if let #pattern_path(#field_name) = &self.#field_name {
+                    if let #pattern_path(#field_name) = &self.#source {
                         if !(#validation) {
                             return None;
                         }
@@ -549,14 +2862,14 @@ fn generate_ref_assignments(
                 }
             } else {
                 quote! {
-                    #field_name: if let #pattern_path(#field_name) = &self.#field_name { #field_name } else { return None }
+                    if let #pattern_path(#field_name) = &self.#source { #field_name } else { return None }
                 }
             }
         } else {
             if let Some(validation) = builder_field.validation {
                 quote! {
-                    #field_name: {
-                        let #field_name = &self.#field_name;
+                    {
+                        let #field_name = &self.#source;
                         if !(#validation) {
                             return None;
                         }
@@ -565,25 +2878,53 @@ fn generate_ref_assignments(
                 }
             } else {
                 quote! {
-                    #field_name: &self.#field_name
+                    &self.#source
                 }
             }
         };
 
-        assignments.push(assignment);
+        let value = if let Some(ref_expr) = builder_field.ref_expr {
+            quote! {
+                {
+                    let #field_name = #value;
+                    #ref_expr
+                }
+            }
+        } else if builder_field.deref_in_ref {
+            quote! { ::core::ops::Deref::deref(#value) }
+        } else if builder_field.copy_in_ref {
+            quote! { *#value }
+        } else {
+            value
+        };
+
+        assignments.push(quote! {
+            #field_name: #value
+        });
     }
 
     Ok(assignments)
 }
 
-/// Generate field assignments for as_mut methods
+/// Generate field assignments for as_mut methods, borrowing from `receiver`, e.g. `self`. With
+/// `report_failures`, a failing field returns `Err(field_name)` rather than `None`. They short-circuit like
+/// [`generate_into_assignments`].
 fn generate_mut_assignments(
     builder_fields: &[BuilderViewField],
+    receiver: &proc_macro2::TokenStream,
+    report_failures: bool,
 ) -> syn::Result<Vec<proc_macro2::TokenStream>> {
     let mut assignments = Vec::new();
 
     for builder_field in builder_fields {
-        let field_name = builder_field.name;
+        let field_name = &builder_field.name;
+        let source = &builder_field.source;
+        let fail = if report_failures {
+            let field_name = field_name.to_string();
+            quote! { return Err(#field_name) }
+        } else {
+            quote! { return None }
+        };
         // Need to rebind lifetime to the original struct
         let final_deref = if builder_field.refs_need_original_lifetime {
             quote! { &mut *#field_name }
@@ -591,35 +2932,54 @@ fn generate_mut_assignments(
             quote! { #field_name }
         };
 
-        let assignment = if let Some(pattern_path) = builder_field.pattern_to_match {
+        let assignment = if let Some(nested) = &builder_field.nested {
+            let nested_mut_name = format_ident!("{}Mut", nested.name);
+            let nested_assignments = generate_mut_assignments(&nested.builder_fields, receiver, report_failures)?;
+            let nested_validation = nested.validation.as_ref().map(|_| {
+                quote! {
+                    if !view.validate() {
+                        #fail;
+                    }
+                }
+            });
+            quote! {
+                #field_name: {
+                    let view = #nested_mut_name {
+                        #(#nested_assignments,)*
+                    };
+                    #nested_validation
+                    view
+                }
+            }
+        } else if let Some(pattern_path) = builder_field.pattern_to_match {
             if let Some(validation) = builder_field.validation {
                 quote! {
-                    #field_name: if let #pattern_path(#field_name) = &mut self.#field_name {
+                    #field_name: if let #pattern_path(#field_name) = &mut #receiver.#source {
                         {
                             let #field_name = &*#field_name;
                             if !(#validation) {
-                                return None;
+                                #fail;
                             }
                         }
                         #final_deref
                     } else {
-                        return None;
+                        #fail;
                     }
                 }
             } else {
                 quote! {
-                    #field_name: if let #pattern_path(#field_name) = &mut self.#field_name { #final_deref } else { return None }
+                    #field_name: if let #pattern_path(#field_name) = &mut #receiver.#source { #final_deref } else { #fail }
                 }
             }
         } else {
             if let Some(validation) = builder_field.validation {
                 quote! {
                     #field_name: {
-                        let #field_name = &mut self.#field_name;
+                        let #field_name = &mut #receiver.#source;
                         {
                             let #field_name = &*#field_name;
                             if !(#validation) {
-                                return None;
+                                #fail;
                             }
                         }
                         #final_deref
@@ -628,7 +2988,7 @@ fn generate_mut_assignments(
             } else {
                 quote! {
                     #field_name: {
-                        let #field_name = &mut self.#field_name;
+                        let #field_name = &mut #receiver.#source;
                         #final_deref
                     }
                 }
@@ -641,6 +3001,19 @@ fn generate_mut_assignments(
     Ok(assignments)
 }
 
+/// Whether the attributes derive the trait, e.g. `Clone` for `#[derive(Debug, Clone)]`
+fn derives_trait(attributes: &[syn::Attribute], trait_name: &str) -> bool {
+    attributes
+        .iter()
+        .filter(|e| e.path().is_ident("derive"))
+        .filter_map(|e| {
+            e.parse_args_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| path.segments.last().is_some_and(|e| e.ident == trait_name))
+}
+
 fn pascal_to_snake_case(s: &str) -> String {
     let mut result = String::new();
     let mut chars = s.chars().peekable();
@@ -649,7 +3022,7 @@ fn pascal_to_snake_case(s: &str) -> String {
         result.push(ch.to_lowercase().next().unwrap());
     }
 
-    while let Some(ch) = chars.next() {
+    for ch in chars {
         if ch.is_uppercase() {
             result.push('_');
         }
@@ -658,3 +3031,297 @@ fn pascal_to_snake_case(s: &str) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Views;
+    use syn::parse_quote;
+
+    /// Expands the views of the original struct, taking its `Variant`, `view_default`, and `ViewDerive`
+    /// attributes like the macro
+    fn expand_to_string(views: Views, mut original_struct: ItemStruct) -> String {
+        let (enum_attributes, variant_config) =
+            crate::parse::extract_variant_attributes(&mut original_struct.attrs).unwrap();
+        let field_defaults = crate::parse::extract_field_defaults(&mut original_struct.attrs).unwrap();
+        let view_derives = crate::parse::extract_view_derives(&mut original_struct.attrs).unwrap();
+        let builder = crate::resolve::resolve(
+            &original_struct,
+            &views,
+            enum_attributes,
+            variant_config,
+            field_defaults,
+            view_derives,
+        )
+        .unwrap();
+        expand(&original_struct, builder).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_doc_examples() {
+        let views: Views = parse_quote! {
+            doc_examples = crate::search,
+            view KeywordSearch {
+                Some(query),
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            struct Search {
+                query: Option<String>,
+                limit: usize,
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        assert!(expanded.contains("\" # Examples\""));
+        assert!(expanded.contains("\" ```rust\""));
+        assert!(expanded.contains("\" use view_types::search::Search;\""));
+        assert!(expanded.contains("\"     limit: Default::default(),\""));
+        assert!(expanded.contains("\" let view = search.as_keyword_search();\""));
+    }
+
+    #[test]
+    fn test_view_derive_arbitrary() {
+        let views: Views = parse_quote! {
+            view KeywordSearch {
+                query,
+            }
+            view SemanticSearch<'a> {
+                vector,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            #[ViewDerive(Clone, arbitrary::Arbitrary)]
+            struct Search<'a> {
+                query: String,
+                vector: &'a [u8],
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let owned_view = quote! { #[derive(Clone, arbitrary::Arbitrary)] struct KeywordSearch }.to_string();
+        let ref_view = quote! { #[derive(Clone)] struct SemanticSearch }.to_string();
+        assert!(expanded.contains(&owned_view));
+        assert!(expanded.contains(&ref_view));
+    }
+
+    #[test]
+    fn test_field_assertions() {
+        let views: Views = parse_quote! {
+            view KeywordSearch {
+                #[view(skip_ref)]
+                limit,
+                pair.0 as low,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            struct Search {
+                limit: usize,
+                pair: (usize, usize),
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let assertions = quote! {
+            fn assert_view_fields(_original: &Search) {
+                fn assert_copy<T: ::core::marker::Copy>() {}
+                let _ = assert_copy::<usize>;
+            }
+        }
+        .to_string();
+        assert!(expanded.contains(&assertions));
+
+        let views: Views = parse_quote! {
+            view KeywordSearch {
+                limit,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            struct Search {
+                limit: usize,
+            }
+        };
+        assert!(!expand_to_string(views, original_struct).contains("assert_view_fields"));
+    }
+
+    #[test]
+    fn test_serde_tag() {
+        let views: Views = parse_quote! {
+            view KeywordSearch {
+                query,
+            }
+            view Paged {
+                limit,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            #[Variant(serde_tag = "type")]
+            struct Search {
+                query: String,
+                limit: usize,
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let tagged_enum = quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+            #[serde(tag = "type")]
+            #[allow(clippy::enum_variant_names)]
+            enum SearchVariant
+        }
+        .to_string();
+        assert!(expanded.contains(&tagged_enum));
+    }
+
+    #[test]
+    fn test_dto() {
+        let views: Views = parse_quote! {
+            #[view(dto = KeywordSearchDto)]
+            pub view KeywordSearch {
+                Some(query),
+                limit,
+            }
+            #[view(dto = PageDto)]
+            pub view Page {
+                limit if *limit > 0,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            pub struct Search {
+                query: Option<String>,
+                limit: usize,
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let dto_struct = quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+            pub struct KeywordSearchDto {
+                query: String,
+                limit: usize,
+            }
+        }
+        .to_string();
+        assert!(expanded.contains(&dto_struct));
+        let round_trip = quote! {
+            impl ::core::convert::From<KeywordSearch> for KeywordSearchDto {
+                fn from(view: KeywordSearch) -> Self {
+                    Self {
+                        query: view.query,
+                        limit: view.limit,
+                    }
+                }
+            }
+
+            impl ::core::convert::From<KeywordSearchDto> for KeywordSearch {
+                fn from(dto: KeywordSearchDto) -> Self {
+                    Self {
+                        query: dto.query,
+                        limit: dto.limit,
+                    }
+                }
+            }
+        }
+        .to_string();
+        assert!(expanded.contains(&round_trip));
+        assert!(expanded.contains(&quote! { impl ::core::convert::TryFrom<PageDto> for Page }.to_string()));
+    }
+
+    #[test]
+    fn test_forward_field_attrs() {
+        let views: Views = parse_quote! {
+            forward_field_attrs(validate),
+            view KeywordSearch {
+                Some(query),
+                limit,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            struct Search {
+                #[validate(length(min = 1))]
+                query: Option<String>,
+                #[serde(default)]
+                limit: usize,
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let forwarded_field = quote! { #[validate(length(min = 1))] query: String }.to_string();
+        assert!(expanded.contains(&forwarded_field));
+        assert!(!expanded.contains("serde"));
+    }
+
+    #[test]
+    fn test_skip_serializing_none() {
+        let views: Views = parse_quote! {
+            skip_serializing_none,
+            #[derive(serde::Serialize)]
+            pub view Filter {
+                query,
+                #[view(serialize_none)]
+                tag,
+                limit,
+            }
+            #[view(dto = PageDto)]
+            pub view Page {
+                query,
+                limit,
+            }
+            pub view Plain {
+                query,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            pub struct Search {
+                query: Option<String>,
+                tag: Option<String>,
+                limit: usize,
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let view_struct = quote! {
+            #[derive(serde::Serialize)]
+            pub struct Filter {
+                #[serde(skip_serializing_if = "Option::is_none")]
+                query: Option<String>,
+                tag: Option<String>,
+                limit: usize,
+            }
+        }
+        .to_string();
+        assert!(expanded.contains(&view_struct));
+        let dto_struct = quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+            pub struct PageDto {
+                #[serde(skip_serializing_if = "Option::is_none")]
+                query: Option<String>,
+                limit: usize,
+            }
+        }
+        .to_string();
+        assert!(expanded.contains(&dto_struct));
+        // Views not deriving serde can not take serde attributes
+        assert!(expanded.contains(&quote! { pub struct Plain { query: Option<String>, } }.to_string()));
+    }
+
+    #[test]
+    fn test_vtable_projections() {
+        let views: Views = parse_quote! {
+            view KeywordSearch {
+                query,
+            }
+            view Paged {
+                limit,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            #[Variant(vtable)]
+            struct Search {
+                query: String,
+                limit: usize,
+            }
+        };
+        let expanded = expand_to_string(views, original_struct);
+        let table = quote! {
+            const QUERY_TABLE: [fn(&Self) -> Option<&String>; 2usize] =
+                [Self::query_of_keyword_search, Self::query_of_paged,];
+        };
+        assert!(expanded.contains(&table.to_string()));
+        assert!(expanded.contains(&quote! { fn query_of_paged(this: &Self) -> Option<&String> { None } }.to_string()));
+        assert!(expanded.contains(&quote! { Self::QUERY_TABLE[discriminant as usize](self) }.to_string()));
+    }
+}
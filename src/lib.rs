@@ -62,16 +62,225 @@ pub fn views(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> p
 }
 
 fn views_impl(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> syn::Result<proc_macro::TokenStream> {
-    let view_spec = syn::parse::<Views>(args.into())?;
+    let mut view_spec = syn::parse::<Views>(args.clone())?;
+    if !view_spec.config.uses.is_empty() {
+        return Ok(import_fragments(args.into(), input.into()).into());
+    }
+    let include_tracking = include_views(&mut view_spec)?;
     
-    let mut original_struct = syn::parse::<ItemStruct>(input.into())?;
-    let enum_attributes = crate::parse::extract_nested_attributes("Variant", &mut original_struct.attrs)?;
-    let resolution = resolve::resolve(&original_struct, &view_spec, enum_attributes)?;
+    let mut original_struct = syn::parse::<ItemStruct>(input)?;
+    let (enum_attributes, variant_config) = crate::parse::extract_variant_attributes(&mut original_struct.attrs)?;
+    let field_defaults = crate::parse::extract_field_defaults(&mut original_struct.attrs)?;
+    let mut view_derives = crate::parse::extract_view_derives(&mut original_struct.attrs)?;
+    if view_spec.config.inherit_derives {
+        for derive in crate::parse::original_derives(&original_struct.attrs)? {
+            if !view_derives.contains(&derive) {
+                view_derives.push(derive);
+            }
+        }
+    }
+    let resolution = resolve::resolve(
+        &original_struct,
+        &view_spec,
+        enum_attributes,
+        variant_config,
+        field_defaults,
+        view_derives,
+    )?;
     
     let generated_code = expand::expand(&original_struct, resolution)?;
     
     Ok(quote::quote! {
         #original_struct
         #generated_code
+        #include_tracking
     }.into())
 }
+
+/// Imports the first `use = fragment` of the args by invoking the `macro_rules!` generated by `fragments!`,
+/// which re-invokes `views` with the fragment inlined and the remaining args
+fn import_fragments(
+    args: proc_macro2::TokenStream,
+    input: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::TokenTree;
+
+    let mut tokens = args.into_iter().peekable();
+    let mut remaining_args = Vec::new();
+    let mut fragment_macro = Vec::new();
+    while let Some(token) = tokens.next() {
+        match &token {
+            TokenTree::Ident(ident) if ident == "use" && fragment_macro.is_empty() => {
+                // `=`
+                tokens.next();
+                // The path, e.g. `crate::shared::paging`
+                while let Some(token) = tokens.next_if(|e| !matches!(e, TokenTree::Punct(p) if p.as_char() == ',')) {
+                    fragment_macro.push(token);
+                }
+                // Optional comma
+                tokens.next();
+            }
+            _ => remaining_args.push(token),
+        }
+    }
+    quote::quote! {
+        #(#fragment_macro)*! {
+            (#(#remaining_args)*)
+            (#input)
+        }
+    }
+}
+
+/// Declares fragments that can be shared between `views` invocations with `use = fragment_name`.
+/// Each fragment is declared as a macro, so it must be declared before use or imported by path.
+///
+/// # Example
+/// ```rust
+/// use view_types::{fragments, views};
+///
+/// fragments! {
+///     paging {
+///         offset,
+///         limit,
+///     }
+/// }
+///
+/// #[views(
+///     use = paging,
+///     pub view Paged {
+///         ..paging,
+///     }
+/// )]
+/// pub struct Search {
+///     query: String,
+///     offset: usize,
+///     limit: usize,
+/// }
+///
+/// #[views(
+///     use = paging,
+///     pub view FilterPage {
+///         ..paging,
+///         tag,
+///     }
+/// )]
+/// pub struct Filter {
+///     tag: String,
+///     offset: usize,
+///     limit: usize,
+/// }
+/// ```
+#[proc_macro]
+pub fn fragments(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let library = match syn::parse::<parse::FragmentLibrary>(input) {
+        Ok(library) => library,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let macros = library.fragments.iter().map(|(name, body)| {
+        quote::quote! {
+            #[allow(unused_macros)]
+            macro_rules! #name {
+                (($($args:tt)*) ($($item:tt)*)) => {
+                    #[::view_types::views(frag #name #body $($args)*)]
+                    $($item)*
+                };
+            }
+            #[allow(unused_imports)]
+            pub(crate) use #name;
+        }
+    });
+    quote::quote! {
+        #(#macros)*
+    }
+    .into()
+}
+
+/// The views macro in function form, taking the original struct followed by `fragments { .. }` and
+/// `views { .. }` blocks with the args of the `views` attribute, and emitting the struct with its views.
+/// The `fragments` block is optional and declares fragments like `fragments!`.
+///
+/// # Example
+/// ```rust
+/// use view_types::define_views;
+///
+/// define_views! {
+///     pub struct Search {
+///         query: String,
+///         offset: usize,
+///         limit: usize,
+///     }
+///     fragments {
+///         paging {
+///             offset,
+///             limit,
+///         }
+///     }
+///     views {
+///         pub view Paged {
+///             ..paging,
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn define_views(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let definition = match syn::parse::<parse::ViewsDefinition>(input) {
+        Ok(definition) => definition,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let original_struct = definition.original_struct;
+    match views_impl(definition.args.into(), quote::quote! { #original_struct }.into()) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Merges the fragments and views of an `include = "path"` file into `view_spec`. The path is resolved
+/// relative to the file invoking the macro, like `include_str!`. Returns an item that makes the compiler
+/// track the included file, so changes to it trigger a rebuild.
+fn include_views(view_spec: &mut Views) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    let Some(include) = view_spec.config.include.take() else {
+        return Ok(None);
+    };
+    let path = match proc_macro::Span::call_site().local_file() {
+        Some(invoking_file) => invoking_file
+            .parent()
+            .map(|e| e.join(include.value()))
+            .unwrap_or_else(|| include.value().into()),
+        None => std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+            .join(include.value()),
+    };
+    let contents = std::fs::read_to_string(&path).map_err(|err| {
+        syn::Error::new(
+            include.span(),
+            format!("Could not read included views file '{}': {err}", path.display()),
+        )
+    })?;
+    let included = syn::parse_str::<Views>(&contents).map_err(|err| {
+        let location = match parse::locate_views_error(&contents) {
+            Some(start) => format!("{}:{}:{}", path.display(), start.line, start.column + 1),
+            None => path.display().to_string(),
+        };
+        syn::Error::new(
+            include.span(),
+            format!("Failed to parse included views file '{location}': {err}"),
+        )
+    })?;
+    if included.config != parse::ViewsConfig::default() {
+        return Err(syn::Error::new(
+            include.span(),
+            format!(
+                "Included views file '{}' may only contain fragments and views",
+                path.display()
+            ),
+        ));
+    }
+    view_spec.fragments.extend(included.fragments);
+    view_spec.view_structs.extend(included.view_structs);
+
+    // `include_str!` resolves relative paths against the invoking file, so pass it an absolute one
+    let path = std::fs::canonicalize(&path).unwrap_or(path).display().to_string();
+    Ok(Some(quote::quote! {
+        const _: &str = include_str!(#path);
+    }))
+}
@@ -4,18 +4,78 @@ use syn::{
 
 const FRAG: &str = "frag";
 const VIEW: &str = "view";
+const VALID: &str = "valid";
+const EXCEPT: &str = "except";
+const FRAGMENTS: &str = "fragments";
+const VIEWS: &str = "views";
+const VIEW_DEFAULT: &str = "view_default";
+const VARIANT: &str = "Variant";
+const VIEW_DERIVE: &str = "ViewDerive";
 
 /// Top-level view specification with fragments and structs
 #[derive(Debug)]
 pub(crate) struct Views {
+    pub config: ViewsConfig,
     pub fragments: Vec<Fragment>,
     pub view_structs: Vec<ViewStruct>
 }
 
+/// Top-level options, e.g. `view_builder = bon`
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ViewsConfig {
+    /// e.g. `bon` in `view_builder = bon`, derives `bon::Builder` on each infallible view
+    pub view_builder: Option<syn::Path>,
+    /// e.g. `"search_views.rs.in"` in `include = "search_views.rs.in"`, a file with more fragments and views
+    pub include: Option<syn::LitStr>,
+    /// `debug_assert_invariants`, wraps `*Mut` structs of validated views in a guard re-checking the
+    /// validations on drop in debug builds
+    pub debug_assert_invariants: bool,
+    /// e.g. `crate::search` in `doc_examples = crate::search`, the module of the original struct, or `crate`
+    /// for a bare `doc_examples`. Adds a doctest creating each view from the original struct to its docs
+    pub doc_examples: Option<syn::Path>,
+    /// e.g. `paging` in `use = paging`, fragments declared with `fragments!` to import
+    pub uses: Vec<syn::Path>,
+    /// e.g. `validate, serde` in `forward_field_attrs(validate, serde)`, attributes of the original fields
+    /// copied onto the view fields
+    pub forward_field_attrs: Vec<Ident>,
+    /// `assert_no_overlap`, errors if an original field is in more than one view
+    pub assert_no_overlap: bool,
+    /// e.g. `Searchable` in `boxed_trait = Searchable`, implemented by every view, generates
+    /// `From<View> for Box<dyn Searchable>`
+    pub boxed_trait: Option<syn::Path>,
+    /// e.g. `borrow` in `ref_method = "borrow"`, the name of the method borrowing a view as its `*Ref` struct
+    pub ref_method: Option<Ident>,
+    /// e.g. `borrow_mut` in `mut_method = "borrow_mut"`, the name of the method borrowing a view as its
+    /// `*Mut` struct
+    pub mut_method: Option<Ident>,
+    /// `inherit_visibility`, views without a declared visibility get the visibility of the original struct
+    pub inherit_visibility: bool,
+    /// e.g. `Shared` in `deref(Shared)`, smart pointers whose `Deref` target is exposed in `*Ref` structs,
+    /// e.g. `&T` instead of `&Shared<T>`
+    pub deref: Vec<syn::Path>,
+    /// `inherit_derives`, the `#[derive(..)]`s on the original struct are also applied to every view
+    pub inherit_derives: bool,
+    /// `cache_validations`, `satisfied_views` evaluates each distinct field pattern and validation once,
+    /// rather than once per view
+    pub cache_validations: bool,
+    /// `bench_helpers`, generates `touch_all_fields` on each view and the variant enum, reading every field
+    /// through `black_box` to anchor microbenchmarks
+    pub bench_helpers: bool,
+    /// `debug_plan`, generates a `VIEW_PLAN` const on the original struct summarizing the resolved views
+    pub debug_plan: bool,
+    /// `skip_serializing_none`, adds `#[serde(skip_serializing_if = "Option::is_none")]` to the `Option`
+    /// fields of serde views and DTOs, unless opted out with `#[view(serialize_none)]`
+    pub skip_serializing_none: bool,
+}
+
 #[derive(Debug)]
 pub(crate) struct Fragment {
     pub name: Ident,
-    pub fields: Vec<FieldItem>,
+    /// Fields, and spreads of other fragments, e.g. `..base` in `frag paging { ..base, cursor }`
+    pub items: Vec<ViewStructFieldKind>,
+    /// e.g. `vector.len() == dim` in `frag name { .. } where valid = vector.len() == dim`, applies to every
+    /// view spreading the fragment
+    pub validation: Option<Expr>,
 }
 
 #[derive(Debug)]
@@ -27,17 +87,177 @@ pub(crate) struct ViewStruct {
     pub ref_attributes: Vec<syn::Attribute>,
     pub mut_attributes: Vec<syn::Attribute>,
     pub visibility: Option<Visibility>,
+    /// e.g. `ratio <= limit` in `view Name { .. } where valid = ratio <= limit`
+    pub validation: Option<Expr>,
+    /// Options from `#[view(..)]` attributes
+    pub config: ViewConfig,
+}
+
+/// Per view options, e.g. `#[view(hash_ignore = ratio)]`
+#[derive(Debug, Default)]
+pub(crate) struct ViewConfig {
+    /// Fields skipped by the generated `Hash`, `PartialEq`, and `Eq` impls
+    pub hash_ignore: Vec<Ident>,
+    /// Generate a `new` constructor taking the fields without a `#[view_default(..)]`
+    pub new: bool,
+    /// Generate a `Copy` snapshot struct of the view and a `*_snapshot` method borrowing the original
+    pub snapshot: bool,
+    /// Emit `#[repr(transparent)]` on the view, which must have a single field
+    pub transparent: bool,
+    /// Generate a `*CowMut` struct borrowing the fields until they are first mutated
+    pub cow_mut: bool,
+    /// Generate a `take_*` method on the original struct, moving the fields into the view with `mem::take`
+    pub take: bool,
+    /// Generate `FromIterator` on a view with a single collection field, defaulting the other fields
+    pub from_iter: bool,
+    /// Generate a `merge_from` method on the `*Mut` struct, cloning the owned fields from a `*Ref` struct
+    pub merge_from: bool,
+    /// Generate `Index<*Field>` on the view, returning the field as `&dyn Debug`
+    pub field_index: bool,
+    /// Fields outside the view, each generating a method on the original struct borrowing the `*Ref` struct
+    /// together with a mutable reference to the field
+    pub and_mut: Vec<Ident>,
+    /// Skip deriving `Clone` and `Copy` on the `*Ref` struct
+    pub no_ref_copy: bool,
+    /// e.g. `QueryRequest` in `into = QueryRequest`, generates `From<View> for QueryRequest`
+    pub into: Option<syn::Type>,
+    /// e.g. `QueryRequest::from_view` in `into_with = "QueryRequest::from_view"`, the function converting the
+    /// view for `into`, rather than moving the fields into the target field by field
+    pub into_with: Option<syn::ExprPath>,
+    /// e.g. `KeywordSearchDto` in `dto = KeywordSearchDto`, generates a serde struct with the fields of the
+    /// view and conversions between the two
+    pub dto: Option<Ident>,
+}
+
+impl ViewConfig {
+    /// Parses the contents of a `#[view(..)]` attribute into this config
+    fn parse_options(&mut self, input: ParseStream) -> Result<()> {
+        let config = self;
+        while !input.is_empty() {
+            let option: Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "hash_ignore" => {
+                    if input.peek(Paren) {
+                        let inner;
+                        parenthesized!(inner in input);
+                        let fields = inner.parse_terminated(Ident::parse, Token![,])?;
+                        config.hash_ignore.extend(fields);
+                    } else {
+                        input.parse::<Token![=]>()?;
+                        config.hash_ignore.push(input.parse()?);
+                    }
+                }
+                "new" => config.new = true,
+                "snapshot" => config.snapshot = true,
+                "transparent" => config.transparent = true,
+                "cow_mut" => config.cow_mut = true,
+                "take" => config.take = true,
+                "from_iter" => config.from_iter = true,
+                "merge_from" => config.merge_from = true,
+                "field_index" => config.field_index = true,
+                "no_ref_copy" => config.no_ref_copy = true,
+                "into" => {
+                    input.parse::<Token![=]>()?;
+                    config.into = Some(input.parse()?);
+                }
+                "into_with" => {
+                    input.parse::<Token![=]>()?;
+                    config.into_with = Some(input.parse::<syn::LitStr>()?.parse()?);
+                }
+                "dto" => {
+                    input.parse::<Token![=]>()?;
+                    config.dto = Some(input.parse()?);
+                }
+                "and_mut" => {
+                    if input.peek(Paren) {
+                        let inner;
+                        parenthesized!(inner in input);
+                        let fields = inner.parse_terminated(Ident::parse, Token![,])?;
+                        config.and_mut.extend(fields);
+                    } else {
+                        input.parse::<Token![=]>()?;
+                        config.and_mut.push(input.parse()?);
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new(option.span(), "Unknown view option"));
+                }
+            }
+
+            // Consume optional comma
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Items that can appear in a view struct definition
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum ViewStructFieldKind {
-    /// Spread a fragment: `..fragment_name`
-    FragmentSpread(Ident),
+    /// Spread a fragment: `..fragment_name`, or `..fragment_name as prefix_` to prefix the names of its fields.
+    /// Fields can be left out with `..fragment_name except { field_name }`
+    FragmentSpread {
+        fragment_name: Ident,
+        prefix: Option<Ident>,
+        except: Vec<Ident>,
+    },
+    /// Nest a fragment as a view of its own: `field_name: ..fragment_name`, see [`nested_view_name`]
+    NestedSpread {
+        field_name: Ident,
+        fragment_name: Ident,
+    },
     /// Individual field: `field_name` or pattern
     Field(FieldItem),
 }
 
+impl Parse for ViewStructFieldKind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Ident) && input.peek2(Token![:]) && input.peek3(Token![..]) {
+            // Nested spread syntax
+            let field_name: Ident = input.parse()?;
+            input.parse::<Token![:]>()?;
+            input.parse::<Token![..]>()?;
+            let fragment_name: Ident = input.parse()?;
+            Ok(ViewStructFieldKind::NestedSpread {
+                field_name,
+                fragment_name,
+            })
+        } else if input.peek(Token![..]) {
+            // Spread syntax
+            input.parse::<Token![..]>()?;
+            let fragment_name: Ident = input.parse()?;
+            let except = if input.peek(Ident) && input.fork().parse::<Ident>()? == EXCEPT {
+                input.parse::<Ident>()?;
+                let excluded;
+                braced!(excluded in input);
+                excluded
+                    .parse_terminated(Ident::parse, Token![,])?
+                    .into_iter()
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let prefix = if input.peek(Token![as]) {
+                input.parse::<Token![as]>()?;
+                Some(input.parse::<Ident>()?)
+            } else {
+                None
+            };
+            Ok(ViewStructFieldKind::FragmentSpread {
+                fragment_name,
+                prefix,
+                except,
+            })
+        } else {
+            // Individual field
+            Ok(ViewStructFieldKind::Field(input.parse::<FieldItem>()?))
+        }
+    }
+}
+
 /// Individual field specification with optional validation
 #[derive(Debug)]
 pub(crate) struct FieldItem {
@@ -48,10 +268,103 @@ pub(crate) struct FieldItem {
     pub validation: Option<Expr>,
     /// Explicit type annotation, e.g. `field: Type` or EnumName::Branch(field: Type)
     pub explicit_type: Option<syn::Type>,
+    /// Options from `#[view(..)]` attributes on the field
+    pub config: FieldConfig,
+    /// e.g. `pair.0` in `pair.0 as low`, an element of a tuple field projected to `field_name`
+    pub tuple_element: Option<TupleElement>,
+    /// e.g. `query` in `text = query`, the field of the original struct exposed as `field_name`
+    pub rename: Option<Ident>,
+    /// e.g. `&mut` in `&mut query`, the view borrows the field from the original struct rather than owning it
+    pub borrow: Option<Borrow>,
+}
+
+/// A borrow of a field owned by the original struct, e.g. `&` in `&query` or `&mut` in `&mut query`
+#[derive(Debug)]
+pub(crate) struct Borrow {
+    pub and_token: Token![&],
+    pub mutability: Option<Token![mut]>,
+}
+
+/// An element of a tuple field of the original struct, e.g. `pair.0`
+#[derive(Debug)]
+pub(crate) struct TupleElement {
+    pub field_name: Ident,
+    pub index: syn::Index,
+}
+
+/// Per field options, e.g. `#[view(str_ref)] query`
+#[derive(Debug, Default)]
+pub(crate) struct FieldConfig {
+    /// Expose the borrowed form of the type in `*Ref` structs, e.g. `&str` instead of `&String`
+    pub str_ref: bool,
+    /// Expose array fields as slices in `*Ref` and `*Mut` structs, e.g. `&[u8]` instead of `&[u8; 32]`
+    pub as_slice: bool,
+    /// Hold the field by value in `*Ref` structs, e.g. `usize` instead of `&usize`. The field must be `Copy`
+    pub skip_ref: bool,
+    /// Generate a `push_*` method for a `Vec` field on the `*Mut` struct. `#[view(push)]` names it after
+    /// the field without a trailing `s`, `#[view(push(tag))]` names it explicitly
+    pub push: Option<Option<Ident>>,
+    /// e.g. `query_text` in `#[view(getter = query_text)]`, the name of the accessor on the variant enum
+    pub getter: Option<Ident>,
+    /// e.g. `&str` in `#[view(ref_type = &str, ref_expr = query.as_str())]`, overrides the type of the field
+    /// in `*Ref` structs
+    pub ref_type: Option<syn::Type>,
+    /// e.g. `query.as_str()` in `#[view(ref_type = &str, ref_expr = query.as_str())]`, overrides the value of
+    /// the field in `*Ref` structs, with the field bound by name as a reference
+    pub ref_expr: Option<Expr>,
+    /// Serialize the field when `None` even with the `skip_serializing_none` option
+    pub serialize_none: bool,
+}
+
+impl FieldConfig {
+    /// Parses the contents of a `#[view(..)]` attribute into this config
+    fn parse_options(&mut self, input: ParseStream) -> Result<()> {
+        let config = self;
+        while !input.is_empty() {
+            let option: Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "str_ref" => config.str_ref = true,
+                "as_slice" => config.as_slice = true,
+                "skip_ref" => config.skip_ref = true,
+                "serialize_none" => config.serialize_none = true,
+                "getter" => {
+                    input.parse::<Token![=]>()?;
+                    config.getter = Some(input.parse()?);
+                }
+                "ref_type" => {
+                    input.parse::<Token![=]>()?;
+                    config.ref_type = Some(input.parse()?);
+                }
+                "ref_expr" => {
+                    input.parse::<Token![=]>()?;
+                    config.ref_expr = Some(input.parse()?);
+                }
+                "push" => {
+                    if input.peek(Paren) {
+                        let content;
+                        parenthesized!(content in input);
+                        config.push = Some(Some(content.parse()?));
+                    } else {
+                        config.push = Some(None);
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new(option.span(), "Unknown field option"));
+                }
+            }
+
+            // Consume optional comma
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Parse for Views {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut config = ViewsConfig::default();
         let mut fragments = Vec::new();
         let mut view_structs = Vec::new();
 
@@ -68,12 +381,16 @@ impl Parse for Views {
                 } else if ident == VIEW {
                     let view_struct = input.parse::<ViewStruct>()?;
                     view_structs.push(view_struct);
+                } else {
+                    parse_config_option(input, &mut config)?;
                 }
-                else {
-                    return Err(syn::Error::new(
-                        ident.span(),
-                        format!("Expected '{FRAG}' or '{VIEW}'"),
-                    ));
+            } else if lookahead.peek(Token![use]) {
+                input.parse::<Token![use]>()?;
+                input.parse::<Token![=]>()?;
+                config.uses.push(input.parse::<syn::Path>()?);
+                // Consume optional comma
+                if input.peek(Token![,]) {
+                    input.parse::<Token![,]>()?;
                 }
             } else if lookahead.peek(Token![#])
                 || lookahead.peek(Token![pub])
@@ -88,13 +405,249 @@ impl Parse for Views {
             }
         }
 
+        add_nested_views(&mut view_structs);
+
         Ok(Views {
+            config,
             fragments,
             view_structs,
         })
     }
 }
 
+/// The name of the view generated for a nested fragment, e.g. `PagingView` for `paging: ..paging`
+pub(crate) fn nested_view_name(fragment_name: &Ident) -> Ident {
+    let fragment_name = fragment_name.to_string();
+    let pascal_case = fragment_name
+        .trim_start_matches("r#")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    quote::format_ident!("{}View", pascal_case)
+}
+
+/// Adds a view for each fragment nested by a view, e.g. `view PagingView { ..paging }` for
+/// `paging: ..paging`, with the visibility and derives of the first view nesting it
+fn add_nested_views(view_structs: &mut Vec<ViewStruct>) {
+    let derives_of = |attributes: &[Attribute]| {
+        attributes
+            .iter()
+            .filter(|e| e.path().is_ident("derive"))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let mut nested_views: Vec<ViewStruct> = Vec::new();
+    for view_struct in view_structs.iter() {
+        for item in &view_struct.items {
+            let ViewStructFieldKind::NestedSpread { fragment_name, .. } = item else {
+                continue;
+            };
+            let name = nested_view_name(fragment_name);
+            if nested_views.iter().any(|e| e.name == name) {
+                continue;
+            }
+            nested_views.push(ViewStruct {
+                name,
+                generics: None,
+                items: vec![ViewStructFieldKind::FragmentSpread {
+                    fragment_name: fragment_name.clone(),
+                    prefix: None,
+                    except: Vec::new(),
+                }],
+                attributes: derives_of(&view_struct.attributes),
+                ref_attributes: derives_of(&view_struct.ref_attributes),
+                mut_attributes: derives_of(&view_struct.mut_attributes),
+                visibility: view_struct.visibility.clone(),
+                validation: None,
+                config: ViewConfig::default(),
+            });
+        }
+    }
+    view_structs.extend(nested_views);
+}
+
+/// Fragments shared between `views` invocations, e.g. `paging { offset, limit }` in `fragments! { .. }`
+#[derive(Debug)]
+pub(crate) struct FragmentLibrary {
+    /// The fragments, with the raw tokens of their braced fields and validation
+    pub fragments: Vec<(Ident, proc_macro2::TokenStream)>,
+}
+
+impl Parse for FragmentLibrary {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut fragments = Vec::new();
+        while !input.is_empty() {
+            // The `frag` keyword is optional here
+            if input.peek(Ident) && input.peek2(Ident) {
+                let fragment_keyword: Ident = input.parse()?;
+                if fragment_keyword != FRAG {
+                    return Err(syn::Error::new(
+                        fragment_keyword.span(),
+                        format!("Expected '{FRAG}' or a fragment name"),
+                    ));
+                }
+            }
+            let name: Ident = input.parse()?;
+            let content;
+            let braces = braced!(content in input);
+            let fields: proc_macro2::TokenStream = content.parse()?;
+            let mut body = proc_macro2::TokenStream::new();
+            braces.surround(&mut body, |tokens| tokens.extend(fields));
+            if let Some(validation) = parse_validation(input, FRAG)? {
+                let valid = Ident::new(VALID, name.span());
+                body.extend(quote::quote! { where #valid = #validation, });
+            }
+            // Validate the fragment now, rather than where it is used
+            let frag = Ident::new(FRAG, name.span());
+            syn::parse2::<Fragment>(quote::quote! { #frag #name #body })?;
+            fragments.push((name, body));
+        }
+        Ok(FragmentLibrary { fragments })
+    }
+}
+
+/// The input of `define_views!`: the original struct, then `fragments { .. }` and `views { .. }` blocks
+#[derive(Debug)]
+pub(crate) struct ViewsDefinition {
+    pub original_struct: syn::ItemStruct,
+    /// The blocks as the args of the `views` attribute, e.g. `frag paging { .. } view Paged { .. }`
+    pub args: proc_macro2::TokenStream,
+}
+
+impl Parse for ViewsDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let original_struct = input.parse::<syn::ItemStruct>()?;
+        let mut fragments = None;
+        let mut views = None;
+        while !input.is_empty() {
+            let block: Ident = input.parse()?;
+            let content;
+            braced!(content in input);
+            let (slot, tokens) = if block == FRAGMENTS {
+                // Fragments as in `fragments!`, where the `frag` keyword is optional
+                let library = content.parse::<FragmentLibrary>()?;
+                let frag = Ident::new(FRAG, block.span());
+                let library_fragments = library.fragments.into_iter().map(|(name, body)| {
+                    quote::quote! { #frag #name #body }
+                });
+                (&mut fragments, quote::quote! { #(#library_fragments)* })
+            } else if block == VIEWS {
+                (&mut views, content.parse()?)
+            } else {
+                return Err(syn::Error::new(
+                    block.span(),
+                    format!("Expected '{FRAGMENTS}' or '{VIEWS}'"),
+                ));
+            };
+            if slot.replace(tokens).is_some() {
+                return Err(syn::Error::new(
+                    block.span(),
+                    format!("Duplicate '{block}' block"),
+                ));
+            }
+        }
+        let Some(views) = views else {
+            return Err(syn::Error::new(
+                input.span(),
+                format!("Expected a '{VIEWS}' block after the struct"),
+            ));
+        };
+        Ok(ViewsDefinition {
+            original_struct,
+            args: quote::quote! { #fragments #views },
+        })
+    }
+}
+
+/// Parses a single top-level option, e.g. `view_builder = bon`
+fn parse_config_option(input: ParseStream, config: &mut ViewsConfig) -> Result<()> {
+    let option: Ident = input.parse()?;
+    match option.to_string().as_str() {
+        "view_builder" => {
+            input.parse::<Token![=]>()?;
+            config.view_builder = Some(input.parse::<syn::Path>()?);
+        }
+        "boxed_trait" => {
+            input.parse::<Token![=]>()?;
+            config.boxed_trait = Some(input.parse::<syn::Path>()?);
+        }
+        "ref_method" => {
+            input.parse::<Token![=]>()?;
+            config.ref_method = Some(input.parse::<syn::LitStr>()?.parse()?);
+        }
+        "mut_method" => {
+            input.parse::<Token![=]>()?;
+            config.mut_method = Some(input.parse::<syn::LitStr>()?.parse()?);
+        }
+        "include" => {
+            input.parse::<Token![=]>()?;
+            config.include = Some(input.parse::<syn::LitStr>()?);
+        }
+        "debug_assert_invariants" => config.debug_assert_invariants = true,
+        "doc_examples" => {
+            let module = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                input.parse::<syn::Path>()?
+            } else {
+                syn::parse_quote!(crate)
+            };
+            if module.leading_colon.is_some() || module.segments[0].ident != "crate" {
+                return Err(syn::Error::new_spanned(
+                    module,
+                    "Expected the crate-relative path of the module of the original struct in `doc_examples`, \
+                     e.g. `doc_examples = crate::search`",
+                ));
+            }
+            config.doc_examples = Some(module);
+        }
+        "assert_no_overlap" => config.assert_no_overlap = true,
+        "inherit_visibility" => config.inherit_visibility = true,
+        "inherit_derives" => config.inherit_derives = true,
+        "cache_validations" => config.cache_validations = true,
+        "bench_helpers" => config.bench_helpers = true,
+        "debug_plan" => config.debug_plan = true,
+        "skip_serializing_none" => config.skip_serializing_none = true,
+        "forward_field_attrs" => {
+            let content;
+            parenthesized!(content in input);
+            let attrs = content.parse_terminated(Ident::parse, Token![,])?;
+            config.forward_field_attrs.extend(attrs);
+        }
+        "deref" => {
+            let content;
+            parenthesized!(content in input);
+            let pointers = content.parse_terminated(syn::Path::parse, Token![,])?;
+            for pointer in pointers {
+                if pointer.segments.iter().any(|e| !e.arguments.is_none()) {
+                    return Err(syn::Error::new_spanned(
+                        pointer,
+                        "Expected the path of a smart pointer without generics in `deref`, e.g. `deref(Shared)`",
+                    ));
+                }
+                config.deref.push(pointer);
+            }
+        }
+        _ => {
+            return Err(syn::Error::new(
+                option.span(),
+                format!("Expected '{FRAG}', '{VIEW}', or a known option"),
+            ));
+        }
+    }
+
+    // Consume optional comma
+    if input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+    }
+    Ok(())
+}
+
 impl Parse for Fragment {
     fn parse(input: ParseStream) -> Result<Self> {
         let fragment_keyword: Ident = input.parse()?;
@@ -109,10 +662,29 @@ impl Parse for Fragment {
         let content;
         braced!(content in input);
 
-        let mut fields = Vec::new();
+        let mut items = Vec::new();
         while !content.is_empty() {
-            let field_spec = content.parse::<FieldItem>()?;
-            fields.push(field_spec);
+            let item = content.parse::<ViewStructFieldKind>()?;
+            match &item {
+                ViewStructFieldKind::NestedSpread { field_name, .. } => {
+                    return Err(syn::Error::new(
+                        field_name.span(),
+                        "Fragments can not nest other fragments, only spread them with `..fragment`",
+                    ));
+                }
+                ViewStructFieldKind::FragmentSpread {
+                    fragment_name,
+                    prefix,
+                    except,
+                } if prefix.is_some() || !except.is_empty() => {
+                    return Err(syn::Error::new(
+                        fragment_name.span(),
+                        "Fragments can only spread other fragments whole, without `as` or `except`",
+                    ));
+                }
+                _ => {}
+            }
+            items.push(item);
 
             // Consume optional comma
             if content.peek(Token![,]) {
@@ -120,16 +692,48 @@ impl Parse for Fragment {
             }
         }
 
-        Ok(Fragment { name, fields })
+        let validation = parse_validation(input, FRAG)?;
+
+        Ok(Fragment {
+            name,
+            items,
+            validation,
+        })
     }
 }
 
+/// Parses an optional validation after the braces of a view or fragment: `where valid = expr`
+fn parse_validation(input: ParseStream, kind: &str) -> Result<Option<Expr>> {
+    if !input.peek(Token![where]) {
+        return Ok(None);
+    }
+    input.parse::<Token![where]>()?;
+    let valid: Ident = input.parse()?;
+    if valid != VALID {
+        return Err(syn::Error::new(
+            valid.span(),
+            format!("Expected '{VALID}' in {kind} validation, e.g. `where {VALID} = expr`"),
+        ));
+    }
+    input.parse::<Token![=]>()?;
+    let validation: Expr = input.parse()?;
+    // Consume optional comma
+    if input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+    }
+    Ok(Some(validation))
+}
+
 impl Parse for ViewStruct {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut attributes = input.call(syn::Attribute::parse_outer)?;
         let ref_attributes = extract_nested_attributes("Ref", &mut attributes)?;
         let mut_attributes = extract_nested_attributes("Mut", &mut attributes)?;
-        let visibility = input.parse::<Visibility>().ok();
+        let config = extract_view_config(&mut attributes)?;
+        let visibility = input
+            .parse::<Visibility>()
+            .ok()
+            .filter(|e| !matches!(e, Visibility::Inherited));
         let ty = input.parse::<Ident>()?;
         if ty.to_string().as_str() != VIEW {
             return Err(syn::Error::new(
@@ -155,16 +759,7 @@ impl Parse for ViewStruct {
 
         let mut items = Vec::new();
         while !content.is_empty() {
-            if content.peek(Token![..]) {
-                // Spread syntax
-                content.parse::<Token![..]>()?;
-                let fragment_name: Ident = content.parse()?;
-                items.push(ViewStructFieldKind::FragmentSpread(fragment_name));
-            } else {
-                // Individual field
-                let field_spec = content.parse::<FieldItem>()?;
-                items.push(ViewStructFieldKind::Field(field_spec));
-            }
+            items.push(content.parse::<ViewStructFieldKind>()?);
 
             // Consume optional comma
             if content.peek(Token![,]) {
@@ -172,6 +767,8 @@ impl Parse for ViewStruct {
             }
         }
 
+        let validation = parse_validation(input, VIEW)?;
+
         Ok(ViewStruct {
             name,
             generics,
@@ -180,13 +777,59 @@ impl Parse for ViewStruct {
             ref_attributes,
             mut_attributes,
             visibility,
+            validation,
+            config,
         })
     }
 }
 
 impl Parse for FieldItem {
     fn parse(input: ParseStream) -> Result<Self> {
-        let (field_name, pattern_to_match, explicit_type) = parse_field_pattern(input)?;
+        let mut config = FieldConfig::default();
+        for attribute in input.call(Attribute::parse_outer)? {
+            if !attribute.path().is_ident(VIEW) {
+                return Err(syn::Error::new_spanned(
+                    attribute,
+                    format!("Only `#[{VIEW}(..)]` attributes are supported on fields"),
+                ));
+            }
+            attribute.parse_args_with(|input: ParseStream| config.parse_options(input))?;
+        }
+        let borrow = if input.peek(Token![&]) {
+            Some(Borrow {
+                and_token: input.parse()?,
+                mutability: input.parse()?,
+            })
+        } else {
+            None
+        };
+        let display_name = if input.peek(Ident) && input.peek2(Token![=]) {
+            let display_name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Some(display_name)
+        } else {
+            None
+        };
+        let (field_name, pattern_to_match, explicit_type, tuple_element) =
+            if input.peek(Ident) && input.peek2(Token![.]) {
+                if let Some(display_name) = &display_name {
+                    return Err(syn::Error::new(
+                        display_name.span(),
+                        "Tuple elements are named with `as`, e.g. `pair.0 as low`",
+                    ));
+                }
+                if let Some(borrow) = &borrow {
+                    return Err(syn::Error::new(
+                        borrow.and_token.span,
+                        "Tuple elements can not be borrowed, borrow the whole tuple field instead",
+                    ));
+                }
+                let (field_name, explicit_type, tuple_element) = parse_tuple_element(input)?;
+                (field_name, None, explicit_type, Some(tuple_element))
+            } else {
+                let (field_name, pattern_to_match, explicit_type) = parse_field_pattern(input)?;
+                (field_name, pattern_to_match, explicit_type, None)
+            };
 
         let validation = if input.peek(Token![if]) {
             input.parse::<Token![if]>()?;
@@ -196,15 +839,50 @@ impl Parse for FieldItem {
             None
         };
 
+        let (field_name, rename) = match display_name {
+            Some(display_name) => (display_name, Some(field_name)),
+            None => (field_name, None),
+        };
+
         Ok(FieldItem {
             pattern_to_match,
             explicit_type,
             validation,
             field_name,
+            config,
+            tuple_element,
+            rename,
+            borrow,
         })
     }
 }
 
+/// Parses a projected tuple element, e.g. `pair.0 as low` or `pair.0 as low: usize`
+fn parse_tuple_element(input: ParseStream) -> Result<(Ident, Option<syn::Type>, TupleElement)> {
+    let tuple_field_name: Ident = input.parse()?;
+    input.parse::<Token![.]>()?;
+    let index: syn::Index = input.parse()?;
+    if !input.peek(Token![as]) {
+        return Err(syn::Error::new(
+            input.span(),
+            format!("Expected `as name` after `{tuple_field_name}.{}`", index.index),
+        ));
+    }
+    input.parse::<Token![as]>()?;
+    let field_name: Ident = input.parse()?;
+    let explicit_type = if input.peek(Token![:]) {
+        input.parse::<Token![:]>()?;
+        Some(input.parse::<syn::Type>()?)
+    } else {
+        None
+    };
+    let tuple_element = TupleElement {
+        field_name: tuple_field_name,
+        index,
+    };
+    Ok((field_name, explicit_type, tuple_element))
+}
+
 /// name, pattern, explicit type
 fn parse_field_pattern(
     input: ParseStream,
@@ -222,12 +900,12 @@ fn parse_field_pattern(
                 let inner_type = inner.parse::<syn::Type>()?;
                 return Ok((field, Some(pattern_to_match), Some(inner_type)));
             }
-            return Ok((field, Some(pattern_to_match), None));
+            Ok((field, Some(pattern_to_match), None))
         } else {
-            return Err(syn::Error::new(
+            Err(syn::Error::new(
                 input.span(),
                 "Expected parentheses containing field to match on",
-            ));
+            ))
         }
     } else {
         // Simple identifier pattern
@@ -238,7 +916,7 @@ fn parse_field_pattern(
             let inner_type = input.parse::<syn::Type>()?;
             return Ok((ident, None, Some(inner_type)));
         }
-        return Ok((ident, None, None));
+        Ok((ident, None, None))
     }
 }
 
@@ -280,11 +958,206 @@ pub(crate) fn extract_nested_attributes(
     attributes.retain(|_| {
         let retain = !&to_remove.contains(&index);
         index += 1;
-        return retain;
+        retain
     });
     Ok(inner_attributes)
 }
 
+/// Extracts and merges the options of `#[view(..)]` attributes
+fn extract_view_config(attributes: &mut Vec<Attribute>) -> syn::Result<ViewConfig> {
+    let mut config = ViewConfig::default();
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(VIEW) {
+            return true;
+        }
+        if let Err(err) = attribute.parse_args_with(|input: ParseStream| config.parse_options(input)) {
+            error.get_or_insert(err);
+        }
+        false
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(config),
+    }
+}
+
+/// Options of the generated variant enum, e.g. `debug_transparent` in `#[Variant(debug_transparent)]`
+#[derive(Debug, Default)]
+pub(crate) struct VariantConfig {
+    /// Generate a `Debug` impl that formats the active view directly
+    pub debug_transparent: bool,
+    /// Fields whose accessors are emitted first, in this order
+    pub field_order: Vec<Ident>,
+    /// Make the enum `repr(u8)` and dispatch the field accessors through a table indexed by its discriminant
+    pub vtable: bool,
+    /// e.g. `"type"` in `serde_tag = "type"`, derives serde's `Serialize` and `Deserialize` on the enum,
+    /// internally tagged with this field
+    pub serde_tag: Option<syn::LitStr>,
+    /// Generate a `*Static` type alias of the enum with every lifetime `'static`
+    pub static_alias: bool,
+    /// Generate a `Hash` impl hashing the discriminant and the active view, if it derives `Hash`
+    pub hash: bool,
+    /// Return a guard from the `*_mut` accessors, which re-checks the validations of the active view when
+    /// dropped in debug builds
+    pub guarded_mut: bool,
+    /// Generate a `*FieldSet` bitset of the original fields, and a `fields_present` method returning those of
+    /// the active view
+    pub field_set: bool,
+    /// Generate a `*Visitor` trait with a method per view, and an `accept` method calling it
+    pub visitor: bool,
+}
+
+impl VariantConfig {
+    /// Parses the contents of a `#[Variant(..)]` attribute, which may contain both attributes for the enum
+    /// and options
+    fn parse_options(&mut self, input: ParseStream) -> Result<Vec<Attribute>> {
+        let config = self;
+        let mut attributes = Vec::new();
+        while !input.is_empty() {
+            if input.peek(Token![#]) {
+                attributes.extend(input.call(Attribute::parse_outer)?);
+                continue;
+            }
+            let option: Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "debug_transparent" => config.debug_transparent = true,
+                "vtable" => config.vtable = true,
+                "static_alias" => config.static_alias = true,
+                "hash" => config.hash = true,
+                "guarded_mut" => config.guarded_mut = true,
+                "visitor" => config.visitor = true,
+                "field_set" => config.field_set = true,
+                "serde_tag" => {
+                    input.parse::<Token![=]>()?;
+                    config.serde_tag = Some(input.parse()?);
+                }
+                "field_order" => {
+                    let inner;
+                    parenthesized!(inner in input);
+                    let fields = inner.parse_terminated(Ident::parse, Token![,])?;
+                    config.field_order.extend(fields);
+                }
+                _ => {
+                    return Err(syn::Error::new(option.span(), "Unknown variant option"));
+                }
+            }
+
+            // Consume optional comma
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+/// Extracts the attributes and options of `#[Variant(..)]` attributes
+pub(crate) fn extract_variant_attributes(
+    attributes: &mut Vec<Attribute>,
+) -> syn::Result<(Vec<Attribute>, VariantConfig)> {
+    let mut config = VariantConfig::default();
+    let mut enum_attributes = Vec::new();
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(VARIANT) || !matches!(attribute.meta, syn::Meta::List(_)) {
+            return true;
+        }
+        match attribute.parse_args_with(|input: ParseStream| config.parse_options(input)) {
+            Ok(attributes) => enum_attributes.extend(attributes),
+            Err(err) => {
+                error.get_or_insert(err);
+            }
+        }
+        false
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok((enum_attributes, config)),
+    }
+}
+
+/// A default value for an original struct field, e.g. `offset = 1` in `#[view_default(offset = 1)]`
+#[derive(Debug)]
+pub(crate) struct FieldDefault {
+    pub field_name: Ident,
+    pub value: Expr,
+}
+
+impl Parse for FieldDefault {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let field_name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(FieldDefault { field_name, value })
+    }
+}
+
+/// Extracts the field defaults of `#[view_default(..)]` attributes
+pub(crate) fn extract_field_defaults(attributes: &mut Vec<Attribute>) -> syn::Result<Vec<FieldDefault>> {
+    let mut field_defaults = Vec::new();
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(VIEW_DEFAULT) {
+            return true;
+        }
+        match attribute.parse_args_with(syn::punctuated::Punctuated::<FieldDefault, Token![,]>::parse_terminated) {
+            Ok(defaults) => field_defaults.extend(defaults),
+            Err(err) => {
+                error.get_or_insert(err);
+            }
+        }
+        false
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(field_defaults),
+    }
+}
+
+/// Extracts the derives of `#[ViewDerive(..)]` attributes, applied to every view
+pub(crate) fn extract_view_derives(attributes: &mut Vec<Attribute>) -> syn::Result<Vec<syn::Path>> {
+    let mut view_derives = Vec::new();
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(VIEW_DERIVE) {
+            return true;
+        }
+        match attribute.parse_args_with(syn::punctuated::Punctuated::<syn::Path, Token![,]>::parse_terminated) {
+            Ok(derives) => view_derives.extend(derives),
+            Err(err) => {
+                error.get_or_insert(err);
+            }
+        }
+        false
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(view_derives),
+    }
+}
+
+/// The derives of the `#[derive(..)]` attributes left on the original struct, for `inherit_derives`
+pub(crate) fn original_derives(attributes: &[Attribute]) -> syn::Result<Vec<syn::Path>> {
+    let mut derives = Vec::new();
+    for attribute in attributes.iter().filter(|e| e.path().is_ident("derive")) {
+        derives.extend(
+            attribute.parse_args_with(syn::punctuated::Punctuated::<syn::Path, Token![,]>::parse_terminated)?,
+        );
+    }
+    Ok(derives)
+}
+
+/// The line and column in `contents` where parsing it as views fails, e.g. for an included views file. Tokens
+/// parsed from a string inside the compiler are all spanned at the macro call, so this re-parses them with
+/// proc-macro2's fallback implementation, which tracks their location in the string.
+pub(crate) fn locate_views_error(contents: &str) -> Option<proc_macro2::LineColumn> {
+    proc_macro2::fallback::force();
+    let start = syn::parse_str::<Views>(contents).err().map(|err| err.span().start());
+    proc_macro2::fallback::unforce();
+    start
+}
+
 #[derive(Debug)]
 struct Attributes {
     pub attributes: Vec<Attribute>,
@@ -316,10 +1189,10 @@ mod tests {
 
         for item in &view_struct.items {
             match item {
-                ViewStructFieldKind::FragmentSpread(fragment_name) => {
+                ViewStructFieldKind::FragmentSpread { fragment_name, .. } => {
                     let fragment_name_str = fragment_name.to_string();
                     if let Some(fragment) = fragment_map.get(&fragment_name_str) {
-                        resolved_fields.extend(&fragment.fields);
+                        resolved_fields.extend(fragment_fields(fragment));
                     } else {
                         return Err(syn::Error::new(
                             fragment_name.span(),
@@ -330,12 +1203,25 @@ mod tests {
                 ViewStructFieldKind::Field(field_spec) => {
                     resolved_fields.push(field_spec);
                 }
+                ViewStructFieldKind::NestedSpread { .. } => {}
             }
         }
 
         Ok(resolved_fields)
     }
 
+    /// Helper to get the fields of a fragment, leaving out its spreads
+    fn fragment_fields(fragment: &Fragment) -> Vec<&FieldItem> {
+        fragment
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ViewStructFieldKind::Field(field_spec) => Some(field_spec),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Helper to determine if a field spec has a validation
     fn has_validation(field_spec: &FieldItem) -> bool {
         field_spec.validation.is_some()
@@ -352,7 +1238,7 @@ mod tests {
 
         let fragment: Fragment = syn::parse2(input).unwrap();
         assert_eq!(fragment.name.to_string(), "all");
-        assert_eq!(fragment.fields.len(), 2);
+        assert_eq!(fragment_fields(&fragment).len(), 2);
     }
 
     #[test]
@@ -371,13 +1257,72 @@ mod tests {
         assert_eq!(view_struct.items.len(), 3);
 
         // Check spread items
-        if let ViewStructFieldKind::FragmentSpread(name) = &view_struct.items[0] {
+        if let ViewStructFieldKind::FragmentSpread { fragment_name: name, .. } = &view_struct.items[0] {
             assert_eq!(name.to_string(), "all");
         } else {
             panic!("Expected spread item");
         }
     }
 
+    #[test]
+    fn test_parse_field_rename() {
+        let field: FieldItem = parse_quote! { text = Some(query: String) };
+        assert_eq!(field.field_name.to_string(), "text");
+        assert_eq!(field.rename.unwrap().to_string(), "query");
+        assert!(field.pattern_to_match.is_some());
+        assert!(field.explicit_type.is_some());
+
+        let field: FieldItem = parse_quote! { query };
+        assert_eq!(field.field_name.to_string(), "query");
+        assert!(field.rename.is_none());
+    }
+
+    #[test]
+    fn test_parse_field_borrow() {
+        let field: FieldItem = parse_quote! { &mut text = query };
+        assert_eq!(field.field_name.to_string(), "text");
+        assert!(field.borrow.unwrap().mutability.is_some());
+
+        let field: FieldItem = parse_quote! { &Some(query) };
+        assert!(field.borrow.unwrap().mutability.is_none());
+        assert!(field.pattern_to_match.is_some());
+
+        let field: FieldItem = parse_quote! { query };
+        assert!(field.borrow.is_none());
+    }
+
+    #[test]
+    fn test_parse_nested_spread() {
+        let input = parse_quote! {
+            frag paging {
+                offset,
+                limit
+            }
+            view SemanticSearch {
+                paging: ..paging,
+                vector
+            }
+        };
+
+        let view_spec: Views = syn::parse2(input).unwrap();
+        let view_names = view_spec
+            .view_structs
+            .iter()
+            .map(|e| e.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(view_names, ["SemanticSearch", "PagingView"]);
+        if let ViewStructFieldKind::NestedSpread {
+            field_name,
+            fragment_name,
+        } = &view_spec.view_structs[0].items[0]
+        {
+            assert_eq!(field_name.to_string(), "paging");
+            assert_eq!(fragment_name.to_string(), "paging");
+        } else {
+            panic!("Expected nested spread item");
+        }
+    }
+
     #[test]
     fn test_parse_fragment_with_validations() {
         let input = parse_quote! {
@@ -388,9 +1333,67 @@ mod tests {
         };
 
         let fragment: Fragment = syn::parse2(input).unwrap();
-        assert_eq!(fragment.fields.len(), 2);
-        assert!(has_validation(&fragment.fields[0]));
-        assert!(!has_validation(&fragment.fields[1]));
+        let fields = fragment_fields(&fragment);
+        assert_eq!(fields.len(), 2);
+        assert!(has_validation(fields[0]));
+        assert!(!has_validation(fields[1]));
+    }
+
+    #[test]
+    fn test_parse_fragment_spread() {
+        let fragment: Fragment = syn::parse2(parse_quote! {
+            frag paging {
+                ..base,
+                cursor
+            }
+        })
+        .unwrap();
+        assert!(matches!(
+            &fragment.items[0],
+            ViewStructFieldKind::FragmentSpread { fragment_name, .. } if fragment_name == "base"
+        ));
+        assert_eq!(fragment_fields(&fragment).len(), 1);
+
+        let error = syn::parse2::<Fragment>(parse_quote! {
+            frag paging {
+                ..base as base_
+            }
+        })
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Fragments can only spread other fragments whole, without `as` or `except`"
+        );
+    }
+
+    #[test]
+    fn test_parse_views_definition() {
+        let definition: ViewsDefinition = syn::parse2(parse_quote! {
+            struct Search {
+                offset: usize,
+            }
+            fragments {
+                paging { offset }
+            }
+            views {
+                view Paged { ..paging }
+            }
+        })
+        .unwrap();
+        assert_eq!(definition.original_struct.ident.to_string(), "Search");
+        let views: Views = syn::parse2(definition.args).unwrap();
+        assert_eq!(views.fragments.len(), 1);
+        assert_eq!(views.view_structs.len(), 1);
+
+        let error = syn::parse2::<ViewsDefinition>(parse_quote! {
+            struct Search {
+                offset: usize,
+            }
+            views {}
+            views {}
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Duplicate 'views' block");
     }
 
     #[test]
@@ -450,4 +1453,74 @@ mod tests {
         assert!(names.contains(&"query".to_owned()));
         assert!(names.contains(&"custom_field".to_owned()));
     }
+
+    #[test]
+    fn test_parse_deref_pointers() {
+        let view_spec: Views = parse_quote! {
+            deref(Shared, crate::ptr::Handle),
+        };
+        let pointers = view_spec
+            .config
+            .deref
+            .iter()
+            .map(|e| quote::quote!(#e).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(pointers, ["Shared", "crate :: ptr :: Handle"]);
+
+        let error = syn::parse_str::<Views>("deref(Shared<T>)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected the path of a smart pointer without generics in `deref`, e.g. `deref(Shared)`"
+        );
+    }
+
+    #[test]
+    fn test_locate_views_error() {
+        let contents = "view Paged {\n    offset,\n    limit,\n}\nfragment paging {\n    offset,\n}\n";
+        let start = locate_views_error(contents).unwrap();
+        assert_eq!((start.line, start.column), (5, 0));
+        assert!(locate_views_error("view Paged {\n    limit\n}\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_doc_examples_module() {
+        let view_spec: Views = parse_quote! {
+            doc_examples,
+        };
+        let module = view_spec.config.doc_examples.unwrap();
+        assert_eq!(quote::quote!(#module).to_string(), "crate");
+
+        let view_spec: Views = parse_quote! {
+            doc_examples = crate::search,
+        };
+        let module = view_spec.config.doc_examples.unwrap();
+        assert_eq!(quote::quote!(#module).to_string(), "crate :: search");
+
+        let error = syn::parse_str::<Views>("doc_examples = search").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected the crate-relative path of the module of the original struct in `doc_examples`, \
+             e.g. `doc_examples = crate::search`"
+        );
+    }
+
+    #[test]
+    fn test_parse_variant_options() {
+        let mut attributes: Vec<Attribute> = vec![
+            parse_quote!(#[Variant(#[derive(Debug)] field_order(offset, limit), visitor, field_set)]),
+            parse_quote!(#[doc = "Search"]),
+        ];
+
+        let (enum_attributes, config) = extract_variant_attributes(&mut attributes).unwrap();
+        assert_eq!(enum_attributes.len(), 1);
+        assert_eq!(attributes.len(), 1);
+        let field_order = config
+            .field_order
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(field_order, ["offset", "limit"]);
+        assert!(config.visitor);
+        assert!(config.field_set);
+    }
 }
@@ -1,51 +1,69 @@
 use std::collections::{HashMap, HashSet};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
     Attribute, Error, Expr, Field, GenericArgument, Generics, Ident, ItemStruct, Lifetime, Type,
     Visibility,
 };
 
-use crate::parse::{ViewStructFieldKind, Views};
+use crate::parse::{
+    nested_view_name, Borrow, FieldDefault, FieldItem, Fragment, TupleElement, VariantConfig, ViewConfig, ViewStruct, ViewStructFieldKind, Views, ViewsConfig,
+};
 
 pub(crate) struct Builder<'a> {
     pub view_structs: Vec<ViewStructBuilder<'a>>,
     pub enum_attributes: Vec<Attribute>,
+    pub variant_config: VariantConfig,
+    pub config: &'a ViewsConfig,
+    /// Lifetime of the borrow of the original struct (or view) in `*Ref`/`*Mut` structs, usually `'original`
+    pub original_lifetime: Lifetime,
+    /// Values of fields omitted from `new` constructors, from `#[view_default(..)]`
+    pub field_defaults: Vec<FieldDefault>,
+    /// Derives of `#[ViewDerive(..)]`, applied to every view
+    pub view_derives: Vec<syn::Path>,
 }
 
 #[derive(Debug)]
 pub(crate) struct ViewStructBuilder<'a> {
     pub name: &'a Ident,
-    original_generics: &'a Option<syn::Generics>,
+    /// The generics declared on the view, or else those of the original struct its fields use
+    original_generics: Option<syn::Generics>,
     pub builder_fields: Vec<BuilderViewField<'a>>,
     pub attributes: &'a Vec<syn::Attribute>,
-    pub visibility: &'a Option<Visibility>,
+    pub visibility: Option<&'a Visibility>,
     /// Generics that are added to the view struct *Ref and *Mut
     ref_generics: Option<syn::Generics>,
     /// Generics that are used in the regular view struct
     regular_generics: Option<syn::Generics>,
     pub ref_attributes: &'a Vec<Attribute>,
     pub mut_attributes: &'a Vec<Attribute>,
+    /// View-level validation over all the fields, e.g. `where valid = expr`, combined with the validations
+    /// of the spread fragments
+    pub validation: Option<Expr>,
+    /// Lifetime of the borrow in the *Ref and *Mut structs, usually `'original`
+    pub original_lifetime: Lifetime,
+    pub config: &'a ViewConfig,
 }
 
 impl<'a> ViewStructBuilder<'a> {
     pub fn new(
-        name: &'a Ident,
-        original_generics: &'a Option<syn::Generics>,
+        view_struct: &'a ViewStruct,
         builder_fields: Vec<BuilderViewField<'a>>,
-        attributes: &'a Vec<syn::Attribute>,
-        visibility: &'a Option<Visibility>,
-        ref_attributes: &'a Vec<Attribute>,
-        mut_attributes: &'a Vec<Attribute>,
+        validation: Option<Expr>,
+        original_lifetime: Lifetime,
     ) -> Self {
         Self {
-            name,
-            original_generics,
+            name: &view_struct.name,
+            original_generics: view_struct.generics.clone(),
             builder_fields,
-            attributes,
-            visibility,
+            attributes: &view_struct.attributes,
+            visibility: view_struct.visibility.as_ref(),
             ref_generics: None,
             regular_generics: None,
-            ref_attributes,
-            mut_attributes,
+            ref_attributes: &view_struct.ref_attributes,
+            mut_attributes: &view_struct.mut_attributes,
+            validation,
+            original_lifetime,
+            config: &view_struct.config,
         }
     }
 
@@ -53,7 +71,9 @@ impl<'a> ViewStructBuilder<'a> {
         if self.ref_generics.is_some() {
             return;
         }
-        let new_lifetime = syn::parse_quote!('original);
+        let new_lifetime = syn::GenericParam::Lifetime(syn::LifetimeParam::new(
+            self.original_lifetime.clone(),
+        ));
         if let Some(original_generics) = &self.original_generics {
             let mut new_generics = original_generics.clone();
             new_generics.params.insert(0, new_lifetime);
@@ -67,14 +87,55 @@ impl<'a> ViewStructBuilder<'a> {
 
     pub fn get_ref_generics(&self) -> Option<&syn::Generics> {
         if let Some(generics) = &self.ref_generics {
-            return Some(generics);
+            Some(generics)
         } else if let Some(original_generics) = &self.original_generics {
-            return Some(original_generics);
+            Some(original_generics)
         } else {
             None
         }
     }
 
+    /// Whether the view holds every field of the original struct as is, so the original can be rebuilt from
+    /// it. Fields extracted with a pattern, projected tuple elements, and fields with a different type lose
+    /// information.
+    pub fn covers_original(&self, original_struct: &ItemStruct) -> bool {
+        let original_fields = self.original_fields().collect::<Vec<_>>();
+        let original_names = original_field_names(original_struct);
+        original_struct.fields.iter().zip(&original_names).all(|(original_field, original_name)| {
+            original_fields.iter().any(|e| {
+                e.name == *original_name
+                    && e.pattern_to_match.is_none()
+                    && type_to_string(&e.regular_struct_field_type) == type_to_string(&original_field.ty)
+            })
+        })
+    }
+
+    /// Whether any field requires unwrapping (pattern matching) or validation, or the view itself or a nested
+    /// view has a validation, i.e. conversions can fail
+    pub fn is_fallible(&self) -> bool {
+        self.validation.is_some()
+            || self
+                .builder_fields
+                .iter()
+                .any(|e| e.nested.as_ref().is_some_and(|nested| nested.validation.is_some()))
+            || self
+                .original_fields()
+                .any(|e| e.pattern_to_match.is_some() || e.validation.is_some())
+    }
+
+    /// The fields taken from the original struct, including those of nested views
+    pub fn original_fields(&self) -> impl Iterator<Item = &BuilderViewField<'a>> {
+        self.builder_fields.iter().flat_map(|e| match &e.nested {
+            Some(nested) => nested.builder_fields.iter().collect::<Vec<_>>(),
+            None => vec![e],
+        })
+    }
+
+    /// Whether a field is borrowed from the original struct, e.g. `&query`, so the view is built from a borrow
+    pub fn borrows_original(&self) -> bool {
+        self.builder_fields.iter().any(|e| e.is_borrowed)
+    }
+
     pub fn get_regular_generics(&self) -> Option<&syn::Generics> {
         if let Some(generics) = &self.regular_generics {
             return Some(generics);
@@ -89,7 +150,12 @@ impl<'a> ViewStructBuilder<'a> {
 #[derive(Debug, Clone)]
 pub(crate) struct BuilderViewField<'a> {
     pub vis: &'a Visibility,
-    pub name: &'a Ident,
+    pub name: Ident,
+    /// Name of the field on the original struct, differs from `name` for projected tuple elements and
+    /// prefixed spreads
+    pub original_name: &'a Ident,
+    /// Access of the field on the original struct, e.g. `query`, or `pair.0` for a projected tuple element
+    pub source: proc_macro2::TokenStream,
     // pub original_struct_field_type: &'a syn::Type,
     /// view struct field type
     pub regular_struct_field_type: syn::Type,
@@ -100,26 +166,73 @@ pub(crate) struct BuilderViewField<'a> {
     /// regular struct type without outer ref/mut and outer Option (possible inner ref/mut still there)
     pub stripped_type: syn::Type,
     pub is_stripped_type_ref: bool,
+    /// Whether the stripped type is the trait object of a `Box<dyn Trait>`, accessed through the box
+    pub is_stripped_type_boxed: bool,
     pub is_ref: bool,
     pub is_mut: bool,
     pub is_option: bool,
+    /// Whether the view borrows the field from the original struct, with `&` or `&mut`
+    pub is_borrowed: bool,
     pub refs_need_original_lifetime: bool,
     pub pattern_to_match: &'a Option<syn::Path>,
     pub validation: &'a Option<Expr>,
+    /// Whether the `*Ref` field is the `Deref` target of the view field, e.g. `&str` for `String`
+    pub deref_in_ref: bool,
+    /// Whether the `*Ref` field is a copy of the view field rather than a reference, with `skip_ref` or for
+    /// raw pointers
+    pub copy_in_ref: bool,
+    /// The value of the `*Ref` field given with `ref_expr`, overriding the reference to the view field
+    pub ref_expr: Option<&'a Expr>,
+    /// Whether the field is serialized when `None` despite `skip_serializing_none`, with `serialize_none`
+    pub serialize_none: bool,
+    /// The name and element type of the `push_*` method generated on the `*Mut` struct
+    pub push_method: Option<(Ident, Type)>,
+    /// The inner type of an `Option` owned by the view, for the `take_*` and `replace_*` methods generated
+    /// on the `*Mut` struct
+    pub owned_option_inner_type: Option<Type>,
+    /// Name of the accessor on the variant enum, the field name unless renamed with `getter`
+    pub getter: Ident,
+    /// Attributes of the original field, none for projected tuple elements
+    pub original_attributes: &'a [Attribute],
+    /// The view of a nested fragment, e.g. `PagingView` for `paging: ..paging`
+    pub nested: Option<NestedView<'a>>,
+    /// The fragment the field was spread from, if any
+    pub fragment: Option<&'a Ident>,
 }
 
+/// A fragment nested in a view as a view of its own, e.g. `paging: ..paging`
+#[derive(Debug, Clone)]
+pub(crate) struct NestedView<'a> {
+    /// e.g. `PagingView`
+    pub name: Ident,
+    pub builder_fields: Vec<BuilderViewField<'a>>,
+    /// The validation of the fragment, checked when the nested view is created
+    pub validation: Option<Expr>,
+}
+
+/// No pattern or validation, for nested fields
+const NONE_PATTERN: &Option<syn::Path> = &None;
+const NONE_VALIDATION: &Option<Expr> = &None;
+
 impl<'a> BuilderViewField<'a> {
     pub fn new(
         original_struct_field: &'a Field,
-        pattern_to_match: &'a Option<syn::Path>,
-        explicit_type: &'a Option<syn::Type>,
-        validation: &'a Option<Expr>,
+        field_item: &'a FieldItem,
+        original_lifetime: &Lifetime,
+        deref_pointers: &[syn::Path],
     ) -> syn::Result<BuilderViewField<'a>> {
+        let FieldItem {
+            pattern_to_match,
+            explicit_type,
+            validation,
+            config,
+            ..
+        } = field_item;
         let original_struct_field_type = &original_struct_field.ty;
         let regular_struct_field_type;
         let ref_struct_field_type;
         let mut_struct_field_type;
-        let refs_need_original_lifetime;
+        
         if let Some(pattern_to_match) = pattern_to_match {
             if let Some(explicit_type) = explicit_type {
                 regular_struct_field_type = explicit_type.clone();
@@ -131,13 +244,42 @@ impl<'a> BuilderViewField<'a> {
             }
         } else {
             if let Some(explicit_type) = explicit_type {
+                if field_item.tuple_element.is_none() && is_incompatible_type(explicit_type, original_struct_field_type) {
+                    return Err(Error::new_spanned(
+                        explicit_type,
+                        format!(
+                            "Type `{}` of field '{}' does not match the type `{}` of the original field",
+                            type_to_string(explicit_type),
+                            field_item.field_name,
+                            type_to_string(original_struct_field_type)
+                        ),
+                    ));
+                }
                 regular_struct_field_type = explicit_type.clone();
+            } else if let Some(tuple_element) = &field_item.tuple_element {
+                regular_struct_field_type = infer_tuple_element_type(original_struct_field_type, tuple_element)?;
             } else {
                 regular_struct_field_type = original_struct_field_type.clone();
             }
         }
-        let (is_ref, is_mut, type_changes) = determine_reference_types(&regular_struct_field_type);
-        refs_need_original_lifetime = type_changes.is_some();
+        // A borrowed field is held like a reference field of the original struct
+        let regular_struct_field_type = match &field_item.borrow {
+            Some(Borrow { and_token, mutability }) => {
+                if matches!(original_struct_field_type, Type::Reference(_)) {
+                    return Err(Error::new(
+                        and_token.span,
+                        format!(
+                            "Field '{}' is already a reference in the original struct",
+                            field_item.field_name
+                        ),
+                    ));
+                }
+                syn::parse_quote!(&#original_lifetime #mutability #regular_struct_field_type)
+            }
+            None => regular_struct_field_type,
+        };
+        let (is_ref, is_mut, type_changes) = determine_reference_types(&regular_struct_field_type, original_lifetime);
+        let refs_need_original_lifetime = type_changes.is_some();
         if let Some((ref_type, mut_type)) = type_changes {
             ref_struct_field_type = ref_type;
             mut_struct_field_type = mut_type;
@@ -145,33 +287,259 @@ impl<'a> BuilderViewField<'a> {
             ref_struct_field_type = regular_struct_field_type.clone();
             mut_struct_field_type = regular_struct_field_type.clone();
         }
+        let ref_struct_field_type = if config.str_ref {
+            if is_ref {
+                return Err(Error::new(
+                    field_item.field_name.span(),
+                    "`str_ref` is only supported on fields owned by the view",
+                ));
+            }
+            borrowed_form(&ref_struct_field_type).ok_or_else(|| {
+                Error::new_spanned(
+                    &ref_struct_field_type,
+                    "`str_ref` is only supported on `String`, `PathBuf`, `OsString`, and `Vec<T>` fields",
+                )
+            })?
+        } else {
+            ref_struct_field_type
+        };
+        if config.skip_ref {
+            if is_ref {
+                return Err(Error::new(
+                    field_item.field_name.span(),
+                    "`skip_ref` is only supported on fields owned by the view",
+                ));
+            }
+            if config.str_ref || config.as_slice {
+                return Err(Error::new(
+                    field_item.field_name.span(),
+                    "`skip_ref` can not be combined with `str_ref` or `as_slice`",
+                ));
+            }
+        }
+        let ref_type = match (&config.ref_type, &config.ref_expr) {
+            (Some(ref_type), Some(_)) => {
+                if is_ref {
+                    return Err(Error::new(
+                        field_item.field_name.span(),
+                        "`ref_type` is only supported on fields owned by the view",
+                    ));
+                }
+                if config.str_ref || config.as_slice || config.skip_ref {
+                    return Err(Error::new(
+                        field_item.field_name.span(),
+                        "`ref_type` can not be combined with `str_ref`, `as_slice` or `skip_ref`",
+                    ));
+                }
+                // An elided lifetime borrows from the original, like the other `*Ref` fields
+                let mut ref_type = ref_type.clone();
+                if let Type::Reference(reference) = &mut ref_type {
+                    reference.lifetime.get_or_insert_with(|| original_lifetime.clone());
+                }
+                Some(ref_type)
+            }
+            (None, None) => None,
+            _ => {
+                return Err(Error::new(
+                    field_item.field_name.span(),
+                    "`ref_type` and `ref_expr` must be given together",
+                ));
+            }
+        };
+        // Smart pointers registered with `deref(..)` expose their target, e.g. `&T` for `Shared<T>`
+        let deref_target = if !is_ref && !config.str_ref && !config.skip_ref && ref_type.is_none() {
+            deref_pointer_target(&regular_struct_field_type, deref_pointers)
+        } else {
+            None
+        };
+        let deref_in_ref = config.str_ref || deref_target.is_some();
+        let ref_struct_field_type = ref_type.or(deref_target).unwrap_or(ref_struct_field_type);
+        // Arrays coerce to slices wherever the `*Ref` and `*Mut` fields are assigned
+        let (ref_struct_field_type, mut_struct_field_type) = if config.as_slice {
+            match &regular_struct_field_type {
+                Type::Array(array) if !is_ref => {
+                    let element_type = &array.elem;
+                    let slice_type: Type = syn::parse_quote!([#element_type]);
+                    (slice_type.clone(), slice_type)
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        &regular_struct_field_type,
+                        "`as_slice` is only supported on array fields owned by the view",
+                    ));
+                }
+            }
+        } else {
+            (ref_struct_field_type, mut_struct_field_type)
+        };
+        let push_method = match &config.push {
+            Some(push_name) => {
+                if is_ref && !is_mut {
+                    return Err(Error::new(
+                        field_item.field_name.span(),
+                        "`push` is not supported on fields behind a shared reference",
+                    ));
+                }
+                let element_type = vec_element_type(&regular_struct_field_type).ok_or_else(|| {
+                    Error::new_spanned(&regular_struct_field_type, "`push` is only supported on `Vec<T>` fields")
+                })?;
+                let singular = match push_name {
+                    Some(push_name) => push_name.to_string(),
+                    None => {
+                        let field_name = field_item.field_name.to_string();
+                        match field_name.strip_suffix('s') {
+                            Some(singular) if !singular.is_empty() => singular.to_owned(),
+                            _ => {
+                                return Err(Error::new(
+                                    field_item.field_name.span(),
+                                    format!(
+                                        "Cannot derive a singular name from '{field_name}', name it with `push(name)`"
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                };
+                Some((format_ident!("push_{}", singular), element_type))
+            }
+            None => None,
+        };
+        // Raw pointers are `Copy`, and a reference to one is only another indirection
+        let copy_in_ref = config.skip_ref || matches!(regular_struct_field_type, Type::Ptr(_));
+        let owned_option_inner_type = (!is_ref && is_option(&regular_struct_field_type))
+            .then(|| stripped_type(&regular_struct_field_type));
         let is_option = is_option(&ref_struct_field_type);
         let stripped_type = stripped_type(&regular_struct_field_type);
-        let is_stripped_type_ref = match stripped_type {
-            syn::Type::Reference(_) => true,
-            _ => false,
+        let is_stripped_type_ref = matches!(stripped_type, syn::Type::Reference(_));
+        let (stripped_type, is_stripped_type_boxed) = match boxed_trait_object(&stripped_type) {
+            Some(trait_object) => (trait_object, true),
+            None => (stripped_type, false),
+        };
+
+        let (original_name, member) = match &original_struct_field.ident {
+            Some(original_name) => (original_name, quote! { #original_name }),
+            // A field of a tuple struct, named by position in the view spec, e.g. `field_0`
+            None => {
+                let original_name = original_field_name(field_item);
+                let index = original_name
+                    .to_string()
+                    .strip_prefix(TUPLE_FIELD_PREFIX)
+                    .and_then(|e| e.parse::<usize>().ok())
+                    .expect("Tuple struct fields are named by position");
+                let index = syn::Index::from(index);
+                (original_name, quote! { #index })
+            }
+        };
+        let (name, source) = match &field_item.tuple_element {
+            Some(TupleElement { index, .. }) => (field_item.field_name.clone(), quote! { #member.#index }),
+            None => (field_item.field_name.clone(), member),
         };
 
         Ok(BuilderViewField {
             vis: &original_struct_field.vis,
-            name: &original_struct_field
-                .ident
-                .as_ref()
-                .expect("Should not be a tuple struct"),
+            name,
+            original_name,
+            source,
             // original_struct_field_type,
             regular_struct_field_type,
             ref_struct_field_type,
             mut_struct_field_type,
             stripped_type,
             is_stripped_type_ref,
+            is_stripped_type_boxed,
             is_ref,
             is_mut,
             is_option,
+            is_borrowed: field_item.borrow.is_some(),
             refs_need_original_lifetime,
             pattern_to_match,
             validation,
+            deref_in_ref,
+            copy_in_ref,
+            ref_expr: config.ref_expr.as_ref(),
+            serialize_none: config.serialize_none,
+            push_method,
+            owned_option_inner_type,
+            getter: config
+                .getter
+                .clone()
+                .unwrap_or_else(|| field_item.field_name.clone()),
+            original_attributes: if field_item.tuple_element.is_some() {
+                &[]
+            } else {
+                &original_struct_field.attrs
+            },
+            nested: None,
+            fragment: None,
         })
     }
+
+    /// The field spread with a prefix, e.g. `sem_vector` for `vector` in `..semantic as sem_`. It still reads
+    /// from the unprefixed field of the original struct
+    pub fn prefixed(&self, prefix: &Ident) -> Self {
+        let name = format_ident!("{}{}", prefix, self.name, span = prefix.span());
+        let getter = if self.getter == self.name {
+            name.clone()
+        } else {
+            self.getter.clone()
+        };
+        BuilderViewField {
+            name,
+            getter,
+            ..self.clone()
+        }
+    }
+
+    /// A field holding the view of a nested fragment, borrowed as its `*Ref` and `*Mut` structs
+    pub fn nested(field_name: &'a Ident, nested: NestedView<'a>, original_lifetime: &Lifetime) -> Self {
+        let view_name = &nested.name;
+        let ref_name = format_ident!("{}Ref", view_name);
+        let mut_name = format_ident!("{}Mut", view_name);
+        let regular_struct_field_type: Type = syn::parse_quote!(#view_name);
+        BuilderViewField {
+            vis: nested.builder_fields[0].vis,
+            name: field_name.clone(),
+            original_name: field_name,
+            source: quote! { #field_name },
+            ref_struct_field_type: syn::parse_quote!(#ref_name<#original_lifetime>),
+            mut_struct_field_type: syn::parse_quote!(#mut_name<#original_lifetime>),
+            stripped_type: regular_struct_field_type.clone(),
+            regular_struct_field_type,
+            is_stripped_type_ref: false,
+            is_stripped_type_boxed: false,
+            is_ref: false,
+            is_mut: false,
+            is_option: false,
+            is_borrowed: false,
+            refs_need_original_lifetime: false,
+            pattern_to_match: NONE_PATTERN,
+            validation: NONE_VALIDATION,
+            deref_in_ref: false,
+            copy_in_ref: false,
+            ref_expr: None,
+            serialize_none: false,
+            push_method: None,
+            owned_option_inner_type: None,
+            getter: field_name.clone(),
+            original_attributes: &[],
+            nested: Some(nested),
+            fragment: None,
+        }
+    }
+}
+
+impl BuilderViewField<'_> {
+    /// Whether `merge_from` can clone this field from the `*Ref` struct into the `*Mut` struct. The `*Ref`
+    /// field must be a reference to the `*Mut` field's type, and reference fields are left alone, since
+    /// overwriting a borrow target wholesale is rarely intended.
+    pub fn is_mergeable(&self) -> bool {
+        !self.is_ref
+            && self.nested.is_none()
+            && self.pattern_to_match.is_none()
+            && !self.deref_in_ref
+            && self.ref_expr.is_none()
+            && !matches!(self.mut_struct_field_type, Type::Slice(_))
+    }
 }
 
 /// Resolves the references to fragments and fields
@@ -179,35 +547,86 @@ pub(crate) fn resolve<'a>(
     original_struct: &'a syn::ItemStruct,
     views: &'a Views,
     enum_attributes: Vec<Attribute>,
+    variant_config: VariantConfig,
+    field_defaults: Vec<FieldDefault>,
+    view_derives: Vec<syn::Path>,
 ) -> syn::Result<Builder<'a>> {
     validate_original_struct(original_struct)?;
     validate_unique_fields(views)?;
 
-    let original_struct_fields = extract_original_fields(&original_struct)?;
+    let original_struct_fields = extract_original_fields(original_struct)?;
+    validate_field_defaults(&field_defaults, &original_struct_fields)?;
+    let original_lifetime = choose_original_lifetime(original_struct, views);
 
-    let builder_view_structs = resolve_field_references(views, &original_struct_fields)?;
+    let mut builder_view_structs =
+        resolve_field_references(views, &original_struct_fields, &original_struct.generics, &original_lifetime)?;
+    validate_variant_config(&variant_config, &builder_view_structs)?;
+    validate_getters(&builder_view_structs)?;
+    if views.config.inherit_visibility {
+        for view_struct in &mut builder_view_structs {
+            view_struct.visibility = view_struct.visibility.or(Some(&original_struct.vis));
+        }
+    }
+    if views.config.assert_no_overlap {
+        validate_no_overlap(&builder_view_structs)?;
+    }
 
     Ok(Builder {
         view_structs: builder_view_structs,
         enum_attributes,
+        variant_config,
+        config: &views.config,
+        original_lifetime,
+        field_defaults,
+        view_derives,
     })
 }
 
+/// Picks the name of the lifetime added for borrows of the original struct or view. This is `'original`,
+/// unless already declared by the original struct or a view, then `'original2`, `'original3`, etc.
+fn choose_original_lifetime(original_struct: &ItemStruct, views: &Views) -> Lifetime {
+    let existing_lifetimes = std::iter::once(&original_struct.generics)
+        .chain(views.view_structs.iter().filter_map(|e| e.generics.as_ref()))
+        .flat_map(|generics| generics.lifetimes())
+        .map(|e| e.lifetime.ident.to_string())
+        .collect::<HashSet<_>>();
+    let mut name = "original".to_owned();
+    let mut suffix = 2;
+    while existing_lifetimes.contains(&name) {
+        name = format!("original{suffix}");
+        suffix += 1;
+    }
+    Lifetime::new(&format!("'{name}"), proc_macro2::Span::call_site())
+}
+
 /// Validate that the original struct is suitable for view generation
 fn validate_original_struct(original_struct: &ItemStruct) -> syn::Result<()> {
     match &original_struct.fields {
-        syn::Fields::Named(_) => Ok(()),
-        syn::Fields::Unnamed(_) => Err(syn::Error::new_spanned(
-            original_struct,
-            "Views macro only supports structs with named fields (not tuple structs)",
-        )),
+        syn::Fields::Named(_) | syn::Fields::Unnamed(_) => Ok(()),
         syn::Fields::Unit => Err(syn::Error::new_spanned(
             original_struct,
-            "Views macro only supports structs with named fields (not unit structs)",
+            "Views macro only supports structs with fields (not unit structs)",
         )),
     }
 }
 
+/// The prefix of the names of the fields of a tuple struct, followed by their position, e.g. `field_0`
+const TUPLE_FIELD_PREFIX: &str = "field_";
+
+/// The names of the fields of the original struct, with the fields of a tuple struct named by position,
+/// e.g. `field_0`
+pub(crate) fn original_field_names(original_struct: &ItemStruct) -> Vec<Ident> {
+    original_struct
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("{}{}", TUPLE_FIELD_PREFIX, index),
+        })
+        .collect()
+}
+
 fn validate_unique_fields(view_spec: &Views) -> syn::Result<()> {
     let mut fragment_names = HashSet::new();
     let mut view_struct_names = HashSet::new();
@@ -220,7 +639,10 @@ fn validate_unique_fields(view_spec: &Views) -> syn::Result<()> {
             ));
         }
         let mut fields = HashSet::new();
-        for field in &fragment.fields {
+        for item in &fragment.items {
+            let ViewStructFieldKind::Field(field) = item else {
+                continue;
+            };
             if !fields.insert(field.field_name.to_string()) {
                 return Err(Error::new(
                     field.field_name.span(),
@@ -244,8 +666,12 @@ fn validate_unique_fields(view_spec: &Views) -> syn::Result<()> {
         let mut regular_fields = HashSet::new();
         for item in &view_struct.items {
             match item {
-                ViewStructFieldKind::FragmentSpread(fragment_name) => {
-                    if !spread_fields.insert(fragment_name.to_string()) {
+                ViewStructFieldKind::FragmentSpread { fragment_name, prefix, .. } => {
+                    let spread = match prefix {
+                        Some(prefix) => format!("{fragment_name} as {prefix}"),
+                        None => fragment_name.to_string(),
+                    };
+                    if !spread_fields.insert(spread) {
                         return Err(Error::new(
                             fragment_name.span(),
                             format!(
@@ -255,13 +681,14 @@ fn validate_unique_fields(view_spec: &Views) -> syn::Result<()> {
                         ));
                     }
                 }
-                ViewStructFieldKind::Field(field_item) => {
-                    if !regular_fields.insert(field_item.field_name.to_string()) {
+                ViewStructFieldKind::Field(FieldItem { field_name, .. })
+                | ViewStructFieldKind::NestedSpread { field_name, .. } => {
+                    if !regular_fields.insert(field_name.to_string()) {
                         return Err(Error::new(
-                            field_item.field_name.span(),
+                            field_name.span(),
                             format!(
                                 "Duplicate field '{}' in view struct '{}'",
-                                field_item.field_name, view_struct.name
+                                field_name, view_struct.name
                             ),
                         ));
                     }
@@ -277,21 +704,16 @@ fn validate_unique_fields(view_spec: &Views) -> syn::Result<()> {
 fn extract_original_fields(
     original_struct: &syn::ItemStruct,
 ) -> syn::Result<HashMap<String, &Field>> {
-    let fields = match &original_struct.fields {
-        syn::Fields::Named(fields) => fields,
-        _ => {
-            return Err(Error::new_spanned(
-                original_struct,
-                "Only structs with named fields are supported",
-            ));
-        }
-    };
+    if matches!(original_struct.fields, syn::Fields::Unit) {
+        return Err(Error::new_spanned(
+            original_struct,
+            "Only structs with fields are supported",
+        ));
+    }
 
     let mut field_map = HashMap::new();
-    for field in &fields.named {
-        if let Some(field_name) = &field.ident {
-            field_map.insert(field_name.to_string(), field);
-        }
+    for (field, field_name) in original_struct.fields.iter().zip(original_field_names(original_struct)) {
+        field_map.insert(field_name.to_string(), field);
     }
 
     Ok(field_map)
@@ -302,9 +724,13 @@ fn extract_original_fields(
 fn resolve_field_references<'a, 'b>(
     view_spec: &'a Views,
     original_fields: &'b HashMap<String, &'a Field>,
+    original_generics: &syn::Generics,
+    original_lifetime: &Lifetime,
 ) -> syn::Result<Vec<ViewStructBuilder<'a>>> {
+    let flat_fragments = flatten_fragments(view_spec)?;
     // fragment name to original field
     let mut builder_fragments: HashMap<String, Vec<BuilderViewField<'a>>> = HashMap::new();
+    let mut fragment_validations: HashMap<String, Expr> = HashMap::new();
     for fragment in &view_spec.fragments {
         let fragment_name = fragment.name.to_string();
         if builder_fragments.contains_key(&fragment_name) {
@@ -313,27 +739,32 @@ fn resolve_field_references<'a, 'b>(
                 format!("Duplicate fragment name found: '{}'", fragment_name),
             ));
         }
+        let flat_fragment = &flat_fragments[&fragment_name];
+        let validation = flat_fragment
+            .validations
+            .iter()
+            .map(|e| (*e).clone())
+            .reduce(|acc, validation| syn::parse_quote!((#acc) && (#validation)));
+        if let Some(validation) = validation {
+            fragment_validations.insert(fragment_name.clone(), validation);
+        }
         let mut binding = builder_fragments
             .entry(fragment_name)
             .insert_entry(Vec::new());
         let builder_fragment_fields = binding.get_mut();
-        for fragment_field_item in &fragment.fields {
-            let fragment_field_name = fragment_field_item.field_name.to_string();
+        for &(fragment_field_item, declaring_fragment) in &flat_fragment.fields {
+            let fragment_field_name = original_field_name(fragment_field_item).to_string();
             if let Some(original_field) = original_fields.get(&fragment_field_name) {
-                builder_fragment_fields.push(BuilderViewField::new(
+                let mut builder_field = BuilderViewField::new(
                     original_field,
-                    &fragment_field_item.pattern_to_match,
-                    &fragment_field_item.explicit_type,
-                    &fragment_field_item.validation,
-                )?);
+                    fragment_field_item,
+                    original_lifetime,
+                    &view_spec.config.deref,
+                )?;
+                builder_field.fragment = Some(declaring_fragment);
+                builder_fragment_fields.push(builder_field);
             } else {
-                return Err(Error::new(
-                    fragment_field_item.field_name.span(),
-                    format!(
-                        "Field '{}' not found in original struct",
-                        fragment_field_name
-                    ),
-                ));
+                return Err(field_not_found(original_field_name(fragment_field_item), original_fields));
             }
         }
     }
@@ -342,54 +773,153 @@ fn resolve_field_references<'a, 'b>(
 
     for view_struct in &view_spec.view_structs {
         let mut builder_fields: Vec<BuilderViewField<'a>> = Vec::new();
+        let mut validations: Vec<&Expr> = view_struct.validation.iter().collect();
         for field_kind in &view_struct.items {
             match field_kind {
-                ViewStructFieldKind::FragmentSpread(fragment_name) => {
+                ViewStructFieldKind::FragmentSpread {
+                    fragment_name,
+                    prefix,
+                    except,
+                } => {
+                    let fragment_name_string = fragment_name.to_string();
+                    let fragment_builder_fields = builder_fragments
+                        .get(&fragment_name_string)
+                        .ok_or_else(|| fragment_not_found(fragment_name, view_spec))?;
+                    for excluded in except {
+                        if !fragment_builder_fields.iter().any(|e| e.name == *excluded) {
+                            return Err(Error::new(
+                                excluded.span(),
+                                format!("Field '{}' is not in fragment '{}'", excluded, fragment_name),
+                            ));
+                        }
+                    }
+                    // The fragment's validation may refer to the excluded fields
+                    if let Some(excluded) = except.first()
+                        && fragment_validations.contains_key(&fragment_name_string)
+                    {
+                        return Err(Error::new(
+                            excluded.span(),
+                            format!(
+                                "Fragment '{}' has a `where valid` validation, so its fields can not be excluded",
+                                fragment_name
+                            ),
+                        ));
+                    }
+                    let fragment_builder_fields = fragment_builder_fields
+                        .iter()
+                        .filter(|e| !except.contains(&e.name))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    let Some(prefix) = prefix else {
+                        builder_fields.extend(fragment_builder_fields);
+                        validations.extend(fragment_validations.get(&fragment_name_string));
+                        continue;
+                    };
+                    // Validations bind the fields by their unprefixed names
+                    if fragment_validations.contains_key(&fragment_name_string)
+                        || fragment_builder_fields.iter().any(|e| e.validation.is_some())
+                    {
+                        return Err(Error::new(
+                            prefix.span(),
+                            format!(
+                                "Fragment '{}' has validations, so its fields can not be prefixed",
+                                fragment_name
+                            ),
+                        ));
+                    }
+                    if let Some(field) = fragment_builder_fields.iter().find(|e| e.ref_expr.is_some()) {
+                        return Err(Error::new(
+                            prefix.span(),
+                            format!(
+                                "Field '{}' of fragment '{}' has a `ref_expr`, so its fields can not be prefixed",
+                                field.name, fragment_name
+                            ),
+                        ));
+                    }
+                    for fragment_builder_field in &fragment_builder_fields {
+                        builder_fields.push(fragment_builder_field.prefixed(prefix));
+                    }
+                }
+                ViewStructFieldKind::NestedSpread {
+                    field_name,
+                    fragment_name,
+                } => {
                     let fragment_name_string = fragment_name.to_string();
                     let fragment_builder_fields = builder_fragments
                         .get(&fragment_name_string)
-                        .ok_or_else(|| {
-                            Error::new(
-                                fragment_name.span(),
-                                format!("Fragment '{}' not found", fragment_name_string),
-                            )
-                        })?;
-                    for fragment_builder_field in fragment_builder_fields {
-                        builder_fields.push(fragment_builder_field.clone());
+                        .ok_or_else(|| fragment_not_found(fragment_name, view_spec))?;
+                    if fragment_builder_fields.is_empty() {
+                        return Err(Error::new(
+                            fragment_name.span(),
+                            format!("Fragment '{}' has no fields, so can not be nested", fragment_name),
+                        ));
+                    }
+                    if original_fields.contains_key(&field_name.to_string()) {
+                        return Err(Error::new(
+                            field_name.span(),
+                            format!(
+                                "Nested field '{}' has the name of a field of the original struct",
+                                field_name
+                            ),
+                        ));
+                    }
+                    if let Some(field) = fragment_builder_fields.iter().find(|e| e.is_borrowed) {
+                        return Err(Error::new(
+                            field_name.span(),
+                            format!(
+                                "Field '{}' of fragment '{}' is borrowed, so the fragment can not be nested",
+                                field.name, fragment_name
+                            ),
+                        ));
                     }
+                    let nested = NestedView {
+                        name: nested_view_name(fragment_name),
+                        builder_fields: fragment_builder_fields.clone(),
+                        validation: fragment_validations.get(&fragment_name_string).cloned(),
+                    };
+                    let mut builder_field = BuilderViewField::nested(field_name, nested, original_lifetime);
+                    builder_field.fragment = Some(fragment_name);
+                    builder_fields.push(builder_field);
                 }
                 ViewStructFieldKind::Field(field_item) => {
-                    let field_name = field_item.field_name.to_string();
+                    let field_name = original_field_name(field_item).to_string();
                     if let Some(original_field) = original_fields.get(&field_name) {
                         builder_fields.push(BuilderViewField::new(
                             original_field,
-                            &field_item.pattern_to_match,
-                            &field_item.explicit_type,
-                            &field_item.validation,
+                            field_item,
+                            original_lifetime,
+                            &view_spec.config.deref,
                         )?);
                     } else {
-                        return Err(Error::new(
-                            field_item.field_name.span(),
-                            format!("Field '{}' not found in original struct", field_name),
-                        ));
+                        return Err(field_not_found(original_field_name(field_item), original_fields));
                     }
                 }
             };
         }
 
+        let validation = validations
+            .into_iter()
+            .cloned()
+            .reduce(|acc, validation| syn::parse_quote!((#acc) && (#validation)));
         let mut struct_builder = ViewStructBuilder::new(
-            &view_struct.name,
-            &view_struct.generics,
+            view_struct,
             builder_fields,
-            &view_struct.attributes,
-            &view_struct.visibility,
-            &view_struct.ref_attributes,
-            &view_struct.mut_attributes,
+            validation,
+            original_lifetime.clone(),
         );
+        if view_struct.generics.is_none() {
+            struct_builder.original_generics = used_generics(struct_builder.original_fields(), original_generics);
+        }
+        validate_view_config(&struct_builder, &view_spec.config)?;
+        validate_and_mut(&struct_builder, original_fields)?;
 
         if struct_builder.builder_fields.iter().any(|e| e.is_ref) {
             struct_builder.add_original_struct_lifetime_to_refs();
         }
+        // A view borrowing from the original struct carries the lifetime of the borrow too
+        if struct_builder.borrows_original() {
+            struct_builder.regular_generics = struct_builder.ref_generics.clone();
+        }
 
         builder_view_structs.push(struct_builder);
     }
@@ -397,123 +927,882 @@ fn resolve_field_references<'a, 'b>(
     Ok(builder_view_structs)
 }
 
-/// Determines the correct reference types.
-/// Outer references may need to change -
-/// Mut lifetimes need to become `'original`, since otherwise it would imply the possibility of having two mutable references,
-/// and `as_*_mut` methods would need `'original: *` (original to live at least as long as all inner lifetimes).
-/// And for ref, all refs need to immutable, because the original struct will be borrowed as `&`.
-/// # Returns
-/// (is_ref, is_mut, (ref_ty, mut_ty))
-/// * `is_ref` - whether the type is a reference type
-/// * `is_mut` - whether the type is a mut reference type
-/// * `(ref_ty, mut_ty)` - the new types if it is a reference type for `Ref` and `Mut` types
-fn determine_reference_types(ty: &syn::Type) -> (bool, bool, Option<(syn::Type, syn::Type)>) {
-    match ty {
-        syn::Type::Reference(reference) => {
-            if reference.mutability.is_some() {
-                let lifetime: Lifetime = syn::parse_quote!('original);
-                (
-                    true,
-                    true,
-                    Some((
-                        syn::Type::Reference(syn::TypeReference {
-                            and_token: reference.and_token.clone(),
-                            lifetime: Some(lifetime.clone()), // todo why can't this remain the same again?
-                            mutability: None,
-                            elem: Box::new(reference.elem.as_ref().clone()),
-                        }),
-                        (syn::Type::Reference(syn::TypeReference {
-                            and_token: reference.and_token.clone(),
-                            lifetime: Some(lifetime),
-                            mutability: reference.mutability.clone(),
-                            elem: Box::new(reference.elem.as_ref().clone()),
-                        })),
-                    )),
-                )
-            } else {
-                (true, false, None)
+/// The generic parameters of the original struct used by the types of `fields`, with their bounds and the
+/// where-predicates on them. Parameters used by the bounds of a used parameter are kept too.
+fn used_generics<'a>(
+    fields: impl Iterator<Item = &'a BuilderViewField<'a>>,
+    original_generics: &syn::Generics,
+) -> Option<syn::Generics> {
+    let mut used = HashSet::new();
+    for field in fields {
+        collect_idents(field.regular_struct_field_type.to_token_stream(), &mut used);
+        collect_idents(field.ref_struct_field_type.to_token_stream(), &mut used);
+    }
+    let param_name = |param: &syn::GenericParam| match param {
+        syn::GenericParam::Lifetime(e) => e.lifetime.to_string(),
+        syn::GenericParam::Type(e) => e.ident.to_string(),
+        syn::GenericParam::Const(e) => e.ident.to_string(),
+    };
+    let predicates = original_generics
+        .where_clause
+        .iter()
+        .flat_map(|e| &e.predicates)
+        .collect::<Vec<_>>();
+    let mentions = |tokens: proc_macro2::TokenStream| {
+        let mut idents = HashSet::new();
+        collect_idents(tokens, &mut idents);
+        idents
+    };
+    // Bounds may mention other parameters, e.g. `T: Into<U>`
+    loop {
+        let mut newly_used = HashSet::new();
+        for param in &original_generics.params {
+            if used.contains(&param_name(param)) {
+                newly_used.extend(mentions(param.to_token_stream()));
             }
         }
-        _ => (false, false, None),
+        for predicate in &predicates {
+            let predicate_mentions = mentions(predicate.to_token_stream());
+            if original_generics
+                .params
+                .iter()
+                .any(|e| used.contains(&param_name(e)) && predicate_mentions.contains(&param_name(e)))
+            {
+                newly_used.extend(predicate_mentions);
+            }
+        }
+        let used_count = used.len();
+        used.extend(newly_used);
+        if used.len() == used_count {
+            break;
+        }
+    }
+    let params = original_generics
+        .params
+        .iter()
+        .filter(|e| used.contains(&param_name(e)))
+        .cloned()
+        .collect::<syn::punctuated::Punctuated<_, syn::Token![,]>>();
+    if params.is_empty() {
+        return None;
     }
+    let where_clause = original_generics.where_clause.as_ref().map(|where_clause| syn::WhereClause {
+        where_token: where_clause.where_token,
+        predicates: where_clause
+            .predicates
+            .iter()
+            .filter(|predicate| {
+                let predicate_mentions = mentions(predicate.to_token_stream());
+                original_generics
+                    .params
+                    .iter()
+                    .all(|e| used.contains(&param_name(e)) || !predicate_mentions.contains(&param_name(e)))
+            })
+            .cloned()
+            .collect(),
+    });
+    Some(syn::Generics {
+        lt_token: original_generics.lt_token,
+        params,
+        gt_token: original_generics.gt_token,
+        where_clause,
+    })
 }
 
-/// Strips the type of references and options.
-fn stripped_type(mut ty: &syn::Type) -> syn::Type {
-    if let syn::Type::Reference(type_reference) = ty {
-        ty = &*type_reference.elem;
-    }
-    if let syn::Type::Path(type_path) = ty {
-        if let Some(last_segment) = type_path.path.segments.last() {
-            if last_segment.ident == "Option" {
-                if let syn::PathArguments::AngleBracketed(args) = &last_segment.arguments {
-                    if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
-                        return inner_type.clone();
-                    }
-                }
+/// Collects the identifiers and lifetimes, e.g. `T` and `'a`, in `tokens`
+fn collect_idents(tokens: proc_macro2::TokenStream, idents: &mut HashSet<String>) {
+    let mut is_lifetime = false;
+    for token in tokens {
+        match token {
+            proc_macro2::TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            proc_macro2::TokenTree::Ident(ident) if is_lifetime => {
+                idents.insert(format!("'{ident}"));
+            }
+            proc_macro2::TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
             }
+            proc_macro2::TokenTree::Punct(punct) => {
+                is_lifetime = punct.as_char() == '\'';
+                continue;
+            }
+            proc_macro2::TokenTree::Literal(_) => {}
         }
+        is_lifetime = false;
     }
+}
 
-    ty.clone()
+/// The fields and validations of a fragment, with the fragments it spreads flattened in order
+struct FlatFragment<'a> {
+    /// Each field with the fragment declaring it
+    fields: Vec<(&'a FieldItem, &'a Ident)>,
+    validations: Vec<&'a Expr>,
 }
 
-fn is_option(ty: &Type) -> bool {
-    match ty {
-        Type::Path(type_path) => {
-            if let Some(last_segment) = type_path.path.segments.last() {
-                return last_segment.ident == "Option";
+/// Flattens the spreads of fragments in other fragments, e.g. `frag paging { ..base, cursor }`, keyed by
+/// fragment name
+fn flatten_fragments(view_spec: &Views) -> syn::Result<HashMap<String, FlatFragment<'_>>> {
+    let mut flat_fragments = HashMap::new();
+    for fragment in &view_spec.fragments {
+        flatten_fragment(fragment, view_spec, &mut flat_fragments, &mut Vec::new())?;
+    }
+    Ok(flat_fragments)
+}
+
+/// Flattens `fragment` and the fragments it spreads into `flat_fragments`. `spreading` holds the names of
+/// the fragments being flattened, to detect cycles.
+fn flatten_fragment<'a>(
+    fragment: &'a Fragment,
+    view_spec: &'a Views,
+    flat_fragments: &mut HashMap<String, FlatFragment<'a>>,
+    spreading: &mut Vec<String>,
+) -> syn::Result<()> {
+    let fragment_name = fragment.name.to_string();
+    if flat_fragments.contains_key(&fragment_name) {
+        return Ok(());
+    }
+    spreading.push(fragment_name.clone());
+    let mut flat_fragment = FlatFragment {
+        fields: Vec::new(),
+        validations: fragment.validation.iter().collect(),
+    };
+    for item in &fragment.items {
+        match item {
+            ViewStructFieldKind::Field(field_item) => {
+                if flat_fragment.fields.iter().any(|(e, _)| e.field_name == field_item.field_name) {
+                    return Err(Error::new(
+                        field_item.field_name.span(),
+                        format!(
+                            "Duplicate field name '{}' in fragment '{}'",
+                            field_item.field_name, fragment.name
+                        ),
+                    ));
+                }
+                flat_fragment.fields.push((field_item, &fragment.name));
             }
-        }
-        Type::Reference(type_reference) => {
-            if let Type::Path(type_path) = type_reference.elem.as_ref() {
-                if let Some(last_segment) = type_path.path.segments.last() {
-                    return last_segment.ident == "Option";
+            ViewStructFieldKind::FragmentSpread { fragment_name: spread_name, .. } => {
+                let spread_name_string = spread_name.to_string();
+                if spreading.contains(&spread_name_string) {
+                    return Err(Error::new(
+                        spread_name.span(),
+                        format!(
+                            "Fragment '{}' spreads itself through '{} -> {}'",
+                            spread_name,
+                            spreading.join(" -> "),
+                            spread_name
+                        ),
+                    ));
+                }
+                let spread = view_spec
+                    .fragments
+                    .iter()
+                    .find(|e| e.name == *spread_name)
+                    .ok_or_else(|| fragment_not_found(spread_name, view_spec))?;
+                flatten_fragment(spread, view_spec, flat_fragments, spreading)?;
+                let spread = &flat_fragments[&spread_name_string];
+                for &(field_item, declaring_fragment) in &spread.fields {
+                    if flat_fragment.fields.iter().any(|(e, _)| e.field_name == field_item.field_name) {
+                        return Err(Error::new(
+                            spread_name.span(),
+                            format!(
+                                "Duplicate field name '{}' in fragment '{}', through the spread of '{}'",
+                                field_item.field_name, fragment.name, spread_name
+                            ),
+                        ));
+                    }
+                    flat_fragment.fields.push((field_item, declaring_fragment));
                 }
+                flat_fragment.validations.extend(spread.validations.iter().copied());
             }
+            // Rejected while parsing
+            ViewStructFieldKind::NestedSpread { .. } => {}
         }
-        _ => {}
-    };
-    false
+    }
+    spreading.pop();
+    flat_fragments.insert(fragment_name, flat_fragment);
+    Ok(())
 }
 
-fn infer_inner_type_for_pattern_match<'a>(
-    ty: &'a Type,
-    pattern_match: &syn::Path,
-) -> syn::Result<Type> {
-    let error = || {
-        Err(syn::Error::new_spanned(
-            pattern_match,
-            "Anonymous pattern deconstructing is not implemented for this type. Add a type definition for the inner e.g. `EnumName::Branch(field: Type)`",
-        ))
+/// The error for a field missing from the original struct, suggesting the closest field name
+fn field_not_found(field_name: &Ident, original_fields: &HashMap<String, &Field>) -> Error {
+    let mut original_field_names = original_fields.keys().cloned().collect::<Vec<_>>();
+    original_field_names.sort();
+    let message = match closest_match(&field_name.to_string(), &original_field_names) {
+        Some(closest) => format!(
+            "Field '{}' not found in original struct, did you mean '{}'?",
+            field_name, closest
+        ),
+        None => format!("Field '{}' not found in original struct", field_name),
     };
-    let is_ref;
-    let ty2 = if let syn::Type::Reference(ref_ty) = ty {
-        is_ref = true;
-        &*ref_ty.elem
+    Error::new(field_name.span(), message)
+}
+
+/// The error for a spread of an unknown fragment, suggesting the closest fragment name
+fn fragment_not_found(fragment_name: &Ident, view_spec: &Views) -> Error {
+    let fragment_name_string = fragment_name.to_string();
+    let mut fragment_names = view_spec
+        .fragments
+        .iter()
+        .map(|e| e.name.to_string())
+        .collect::<Vec<_>>();
+    fragment_names.sort();
+    let message = if let Some(closest) = closest_match(&fragment_name_string, &fragment_names) {
+        format!(
+            "Fragment '{}' not found, did you mean '{}'?",
+            fragment_name_string, closest
+        )
+    } else if fragment_names.is_empty() {
+        format!("Fragment '{}' not found", fragment_name_string)
     } else {
-        is_ref = false;
-        ty
+        format!(
+            "Fragment '{}' not found, available fragments are '{}'",
+            fragment_name_string,
+            fragment_names.join("', '")
+        )
     };
-    let inner_type: &syn::Type = if let syn::Type::Path(ty) = ty2 {
-        let ty_last_segment = &ty.path.segments.last().unwrap();
-        let ty_last_segment_name = ty_last_segment.ident.to_string();
-        match ty_last_segment_name.as_str() {
-            "Result" => {
-                let arguments = &ty.path.segments.last().unwrap().arguments;
-                match arguments {
-                    syn::PathArguments::AngleBracketed(generic_arguments) => {
-                        let mut args = generic_arguments.args.iter();
-                        let ok = args.next().unwrap();
-                        let Some(err) = args.next() else {
-                            return error();
-                        };
-                        let is_ok = pattern_match
-                            .segments
-                            .last()
-                            .unwrap()
-                            .ident
-                            .to_string()
+    Error::new(fragment_name.span(), message)
+}
+
+/// Validate that each `#[view_default(..)]` is for a unique field of the original struct
+fn validate_field_defaults(
+    field_defaults: &[FieldDefault],
+    original_fields: &HashMap<String, &Field>,
+) -> syn::Result<()> {
+    let mut seen = HashSet::new();
+    for field_default in field_defaults {
+        let field_name = field_default.field_name.to_string();
+        if !original_fields.contains_key(&field_name) {
+            return Err(Error::new(
+                field_default.field_name.span(),
+                format!("Field '{}' in `view_default` not found in original struct", field_name),
+            ));
+        }
+        if !seen.insert(field_name) {
+            return Err(Error::new(
+                field_default.field_name.span(),
+                format!("Duplicate default for field '{}'", field_default.field_name),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate that each field in `field_order` has an accessor on the variant enum, i.e. is in a view
+fn validate_variant_config(
+    variant_config: &VariantConfig,
+    view_structs: &[ViewStructBuilder],
+) -> syn::Result<()> {
+    for ordered in &variant_config.field_order {
+        if !view_structs
+            .iter()
+            .flat_map(|e| &e.builder_fields)
+            .any(|e| e.name == *ordered)
+        {
+            return Err(Error::new(
+                ordered.span(),
+                format!("Field '{}' in `field_order` is not in any view", ordered),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate that each field has one accessor name on the variant enum and no two fields share one
+fn validate_getters(view_structs: &[ViewStructBuilder]) -> syn::Result<()> {
+    let mut field_to_getter: HashMap<&Ident, &Ident> = HashMap::new();
+    let mut getter_to_field: HashMap<&Ident, &Ident> = HashMap::new();
+    for field in view_structs.iter().flat_map(|e| &e.builder_fields) {
+        let getter = field_to_getter.entry(&field.name).or_insert(&field.getter);
+        if *getter != &field.getter {
+            return Err(Error::new(
+                field.getter.span(),
+                format!(
+                    "Field '{}' has conflicting getters '{}' and '{}'",
+                    field.name, getter, field.getter
+                ),
+            ));
+        }
+        let other_field = getter_to_field.entry(&field.getter).or_insert(&field.name);
+        if **other_field != field.name {
+            return Err(Error::new(
+                field.getter.span(),
+                format!(
+                    "Fields '{}' and '{}' have the same getter '{}'",
+                    other_field, field.name, field.getter
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every original field is in at most one view
+fn validate_no_overlap(view_structs: &[ViewStructBuilder]) -> syn::Result<()> {
+    let mut field_to_view: HashMap<&Ident, &Ident> = HashMap::new();
+    for view_struct in view_structs {
+        for field in view_struct.original_fields() {
+            let view = field_to_view.entry(field.original_name).or_insert(view_struct.name);
+            if *view != view_struct.name {
+                return Err(Error::new(
+                    field.name.span(),
+                    format!(
+                        "Field '{}' is in views '{}' and '{}', but `assert_no_overlap` is set",
+                        field.original_name, view, view_struct.name
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the fields in `and_mut` exist and are disjoint from the view
+fn validate_and_mut(view_struct: &ViewStructBuilder, original_fields: &HashMap<String, &Field>) -> syn::Result<()> {
+    for field_name in &view_struct.config.and_mut {
+        if !original_fields.contains_key(&field_name.to_string()) {
+            return Err(Error::new(
+                field_name.span(),
+                format!("Field '{}' in `and_mut` not found in original struct", field_name),
+            ));
+        }
+        if view_struct.original_fields().any(|e| e.original_name == field_name) {
+            return Err(Error::new(
+                field_name.span(),
+                format!(
+                    "Field '{}' in `and_mut` is in view '{}', so can not be borrowed mutably alongside it",
+                    field_name, view_struct.name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate that the fields referenced by `#[view(..)]` options are in the view
+fn validate_view_config(view_struct: &ViewStructBuilder, config: &ViewsConfig) -> syn::Result<()> {
+    // Building a fallible view directly would skip its patterns and validations
+    if config.view_builder.is_some() && view_struct.is_fallible() {
+        return Err(Error::new(
+            view_struct.name.span(),
+            format!(
+                "`view_builder` requires view '{}' to have no patterns or validations",
+                view_struct.name
+            ),
+        ));
+    }
+    // Both are built from the types of the `*Ref` fields
+    if (view_struct.config.cow_mut || view_struct.config.snapshot)
+        && let Some(field) = view_struct.builder_fields.iter().find(|e| e.ref_expr.is_some())
+    {
+        return Err(Error::new(
+            field.name.span(),
+            format!(
+                "`ref_type` is not supported on view '{}', since it has `cow_mut` or `snapshot`",
+                view_struct.name
+            ),
+        ));
+    }
+    if view_struct.config.cow_mut {
+        if let Some(field) = view_struct.builder_fields.iter().find(|e| e.is_ref) {
+            return Err(Error::new(
+                field.name.span(),
+                format!(
+                    "`cow_mut` requires the fields of view '{}' to be owned, but '{}' is a reference",
+                    view_struct.name, field.name
+                ),
+            ));
+        }
+        if view_struct.builder_fields.is_empty() {
+            return Err(Error::new(
+                view_struct.name.span(),
+                format!("`cow_mut` requires view '{}' to have fields", view_struct.name),
+            ));
+        }
+    }
+    if view_struct.config.merge_from && !view_struct.builder_fields.iter().any(|e| e.is_mergeable()) {
+        return Err(Error::new(
+            view_struct.name.span(),
+            format!(
+                "`merge_from` requires view '{}' to have an owned field without a pattern",
+                view_struct.name
+            ),
+        ));
+    }
+    if let Some(into_with) = &view_struct.config.into_with
+        && view_struct.config.into.is_none()
+    {
+        return Err(Error::new_spanned(
+            into_with,
+            format!("`into_with` requires `into` on view '{}'", view_struct.name),
+        ));
+    }
+    if view_struct.config.transparent && view_struct.builder_fields.len() != 1 {
+        return Err(Error::new(
+            view_struct.name.span(),
+            format!(
+                "`transparent` requires view '{}' to have exactly one field, found {}",
+                view_struct.name,
+                view_struct.builder_fields.len()
+            ),
+        ));
+    }
+    if view_struct.config.from_iter {
+        if view_struct.is_fallible() {
+            return Err(Error::new(
+                view_struct.name.span(),
+                format!(
+                    "`from_iter` requires view '{}' to have no patterns or validations",
+                    view_struct.name
+                ),
+            ));
+        }
+        let collection_fields = view_struct
+            .builder_fields
+            .iter()
+            .filter(|e| collection_element_type(&e.regular_struct_field_type).is_some())
+            .count();
+        if collection_fields != 1 {
+            return Err(Error::new(
+                view_struct.name.span(),
+                format!(
+                    "`from_iter` requires view '{}' to have exactly one owned collection field, e.g. a `Vec<T>`, found {}",
+                    view_struct.name, collection_fields
+                ),
+            ));
+        }
+    }
+    if let Some(dto) = &view_struct.config.dto {
+        if let Some(field) = view_struct.builder_fields.iter().find(|e| e.is_ref) {
+            return Err(Error::new(
+                field.name.span(),
+                format!(
+                    "`dto` requires the fields of view '{}' to be owned, but '{}' is a reference",
+                    view_struct.name, field.name
+                ),
+            ));
+        }
+        if let Some(field) = view_struct.builder_fields.iter().find(|e| e.nested.is_some()) {
+            return Err(Error::new(
+                dto.span(),
+                format!(
+                    "`dto` is not supported on view '{}', since '{}' is a nested fragment",
+                    view_struct.name, field.name
+                ),
+            ));
+        }
+    }
+    if view_struct.config.take
+        && let Some(field) = view_struct.builder_fields.iter().find(|e| e.is_ref)
+    {
+        return Err(Error::new(
+            field.name.span(),
+            format!(
+                "`take` requires the fields of view '{}' to be owned, but '{}' is a reference",
+                view_struct.name, field.name
+            ),
+        ));
+    }
+    for (option, enabled) in [
+        ("cow_mut", view_struct.config.cow_mut),
+        ("snapshot", view_struct.config.snapshot),
+        ("take", view_struct.config.take),
+    ] {
+        if enabled && let Some(field) = view_struct.builder_fields.iter().find(|e| e.nested.is_some()) {
+            return Err(Error::new(
+                field.name.span(),
+                format!(
+                    "`{}` is not supported on view '{}', since '{}' is a nested fragment",
+                    option, view_struct.name, field.name
+                ),
+            ));
+        }
+    }
+    if view_struct.config.snapshot
+        && let Some(field) = view_struct.builder_fields.iter().find(|e| e.is_ref)
+    {
+        return Err(Error::new(
+            field.name.span(),
+            format!(
+                "`snapshot` requires the fields of view '{}' to be owned `Copy` types, but '{}' is a reference",
+                view_struct.name, field.name
+            ),
+        ));
+    }
+    for ignored in &view_struct.config.hash_ignore {
+        if !view_struct.builder_fields.iter().any(|e| e.name == *ignored) {
+            return Err(Error::new(
+                ignored.span(),
+                format!(
+                    "Field '{}' in `hash_ignore` is not in view '{}'",
+                    ignored, view_struct.name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Determines the correct reference types.
+/// Outer references may need to change -
+/// Mut lifetimes need to become `'original` (see [`choose_original_lifetime`]), since otherwise it would imply the possibility of having two mutable references,
+/// and `as_*_mut` methods would need `'original: *` (original to live at least as long as all inner lifetimes).
+/// And for ref, all refs need to immutable, because the original struct will be borrowed as `&`.
+/// # Returns
+/// (is_ref, is_mut, (ref_ty, mut_ty))
+/// * `is_ref` - whether the type is a reference type
+/// * `is_mut` - whether the type is a mut reference type
+/// * `(ref_ty, mut_ty)` - the new types if it is a reference type for `Ref` and `Mut` types
+fn determine_reference_types(
+    ty: &syn::Type,
+    original_lifetime: &Lifetime,
+) -> (bool, bool, Option<(syn::Type, syn::Type)>) {
+    match ty {
+        syn::Type::Reference(reference) => {
+            if reference.mutability.is_some() {
+                let lifetime = original_lifetime.clone();
+                (
+                    true,
+                    true,
+                    Some((
+                        syn::Type::Reference(syn::TypeReference {
+                            and_token: reference.and_token,
+                            lifetime: Some(lifetime.clone()), // todo why can't this remain the same again?
+                            mutability: None,
+                            elem: Box::new(reference.elem.as_ref().clone()),
+                        }),
+                        (syn::Type::Reference(syn::TypeReference {
+                            and_token: reference.and_token,
+                            lifetime: Some(lifetime),
+                            mutability: reference.mutability,
+                            elem: Box::new(reference.elem.as_ref().clone()),
+                        })),
+                    )),
+                )
+            } else {
+                (true, false, None)
+            }
+        }
+        _ => (false, false, None),
+    }
+}
+
+/// The `Deref` target of a smart pointer registered with `deref(..)`, e.g. `T` for `Shared<T>`. Pointers are
+/// matched by the last segment of their path.
+fn deref_pointer_target(ty: &Type, deref_pointers: &[syn::Path]) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_segment = type_path.path.segments.last()?;
+    let is_registered = deref_pointers
+        .iter()
+        .filter_map(|e| e.segments.last())
+        .any(|e| e.ident == last_segment.ident);
+    if !is_registered {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(arguments) = &last_segment.arguments else {
+        return None;
+    };
+    let mut type_arguments = arguments.args.iter().filter_map(|e| match e {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match (type_arguments.next(), type_arguments.next()) {
+        (Some(target), None) => Some(target.clone()),
+        _ => None,
+    }
+}
+
+/// Types of the standard library prelude, which are not shadowed by type aliases in practice
+const PRELUDE_TYPES: &[&str] = &[
+    "bool", "char", "str", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+    "u128", "usize", "String", "Vec", "Option", "Result", "Box",
+];
+
+/// Whether an explicit view field type can obviously not be the type of the original field, e.g. `String` for
+/// `Option<usize>`. Other paths may be type aliases, so only prelude types and differing type shapes are compared.
+fn is_incompatible_type(explicit: &Type, original: &Type) -> bool {
+    match (explicit, original) {
+        (Type::Paren(explicit), _) => is_incompatible_type(&explicit.elem, original),
+        (_, Type::Paren(original)) => is_incompatible_type(explicit, &original.elem),
+        (Type::Group(explicit), _) => is_incompatible_type(&explicit.elem, original),
+        (_, Type::Group(original)) => is_incompatible_type(explicit, &original.elem),
+        (Type::Path(explicit), Type::Path(original)) => {
+            let (Some(explicit), Some(original)) = (explicit.path.segments.last(), original.path.segments.last())
+            else {
+                return false;
+            };
+            if explicit.ident != original.ident {
+                let is_prelude = |ident: &Ident| PRELUDE_TYPES.iter().any(|e| ident == e);
+                return is_prelude(&explicit.ident) && is_prelude(&original.ident);
+            }
+            let type_arguments = |arguments: &syn::PathArguments| match arguments {
+                syn::PathArguments::AngleBracketed(arguments) => arguments
+                    .args
+                    .iter()
+                    .filter_map(|e| match e {
+                        GenericArgument::Type(ty) => Some(ty.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+                _ => Vec::new(),
+            };
+            let explicit_arguments = type_arguments(&explicit.arguments);
+            let original_arguments = type_arguments(&original.arguments);
+            explicit_arguments.len() == original_arguments.len()
+                && explicit_arguments
+                    .iter()
+                    .zip(&original_arguments)
+                    .any(|(explicit, original)| is_incompatible_type(explicit, original))
+        }
+        // Paths may be aliases, and macros may expand to, any other shape
+        (Type::Path(_) | Type::Macro(_) | Type::Verbatim(_), _)
+        | (_, Type::Path(_) | Type::Macro(_) | Type::Verbatim(_)) => false,
+        (Type::Reference(explicit), Type::Reference(original)) => {
+            explicit.mutability.is_some() != original.mutability.is_some()
+                || is_incompatible_type(&explicit.elem, &original.elem)
+        }
+        (Type::Tuple(explicit), Type::Tuple(original)) => {
+            explicit.elems.len() != original.elems.len()
+                || explicit
+                    .elems
+                    .iter()
+                    .zip(&original.elems)
+                    .any(|(explicit, original)| is_incompatible_type(explicit, original))
+        }
+        (Type::Array(explicit), Type::Array(original)) => is_incompatible_type(&explicit.elem, &original.elem),
+        (Type::Slice(explicit), Type::Slice(original)) => is_incompatible_type(&explicit.elem, &original.elem),
+        (Type::Reference(_) | Type::Tuple(_) | Type::Array(_) | Type::Slice(_), _)
+        | (_, Type::Reference(_) | Type::Tuple(_) | Type::Array(_) | Type::Slice(_)) => {
+            std::mem::discriminant(explicit) != std::mem::discriminant(original)
+        }
+        _ => false,
+    }
+}
+
+/// Formats a type or other tokens for messages, e.g. `Vec<u8>` rather than `Vec < u8 >`
+pub(crate) fn type_to_string(ty: &impl quote::ToTokens) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+        .replace("' ", "'")
+}
+
+/// The borrowed form of owned types that deref to it, e.g. `str` for `String`
+fn borrowed_form(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_segment = type_path.path.segments.last()?;
+    match last_segment.ident.to_string().as_str() {
+        "String" => Some(syn::parse_quote!(str)),
+        "PathBuf" => Some(syn::parse_quote!(::std::path::Path)),
+        "OsString" => Some(syn::parse_quote!(::std::ffi::OsStr)),
+        "Vec" => match &last_segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(inner_type)) => Some(syn::parse_quote!([#inner_type])),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The element type of an owned standard collection, e.g. `T` for `Vec<T>` or `HashSet<T>`
+pub(crate) fn collection_element_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_segment = type_path.path.segments.last()?;
+    let collections = ["Vec", "VecDeque", "LinkedList", "HashSet", "BTreeSet", "BinaryHeap"];
+    if !collections.iter().any(|e| last_segment.ident == e) {
+        return None;
+    }
+    match &last_segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner_type)) => Some(inner_type.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The element type of a `Vec<T>`, possibly behind a reference
+fn vec_element_type(mut ty: &Type) -> Option<Type> {
+    if let Type::Reference(type_reference) = ty {
+        ty = &*type_reference.elem;
+    }
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_segment = type_path.path.segments.last()?;
+    if last_segment.ident != "Vec" {
+        return None;
+    }
+    match &last_segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner_type)) => Some(inner_type.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The candidate closest to `name` by edit distance, if close enough to likely be a typo of it
+fn closest_match<'b>(name: &str, candidates: &'b [String]) -> Option<&'b str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous_row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+    previous_row[b.len()]
+}
+
+/// The name of the original struct field a field item reads from, e.g. `pair` for `pair.0 as low`, or
+/// `query` for `text = query`
+fn original_field_name(field_item: &FieldItem) -> &Ident {
+    match (&field_item.tuple_element, &field_item.rename) {
+        (Some(tuple_element), _) => &tuple_element.field_name,
+        (None, Some(rename)) => rename,
+        (None, None) => &field_item.field_name,
+    }
+}
+
+/// The type of a tuple element, e.g. `usize` for `pair.0` of `pair: (usize, String)`
+fn infer_tuple_element_type(ty: &Type, tuple_element: &TupleElement) -> syn::Result<Type> {
+    let TupleElement { field_name, index } = tuple_element;
+    let Type::Tuple(tuple) = ty else {
+        return Err(Error::new(
+            field_name.span(),
+            format!(
+                "Cannot infer the type of '{field_name}.{}', specify it with `{field_name}.{} as name: Type`",
+                index.index, index.index
+            ),
+        ));
+    };
+    tuple.elems.iter().nth(index.index as usize).cloned().ok_or_else(|| {
+        Error::new(
+            index.span,
+            format!("Tuple field '{field_name}' has no element {}", index.index),
+        )
+    })
+}
+
+/// The trait object of a `Box<dyn Trait>`, e.g. `dyn Fn()` for `Box<dyn Fn()>`
+fn boxed_trait_object(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_segment = type_path.path.segments.last()?;
+    if last_segment.ident != "Box" {
+        return None;
+    }
+    match &last_segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            // Parenthesized so it can be borrowed, e.g. `&(dyn Fn() + Send)`
+            Some(GenericArgument::Type(Type::TraitObject(trait_object))) if trait_object.bounds.len() > 1 => {
+                Some(syn::parse_quote!((#trait_object)))
+            }
+            Some(GenericArgument::Type(inner_type @ Type::TraitObject(_))) => Some(inner_type.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Strips the type of references and options.
+fn stripped_type(mut ty: &syn::Type) -> syn::Type {
+    if let syn::Type::Reference(type_reference) = ty {
+        ty = &*type_reference.elem;
+    }
+    if let syn::Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+            && last_segment.ident == "Option"
+                && let syn::PathArguments::AngleBracketed(args) = &last_segment.arguments
+                    && let Some(GenericArgument::Type(inner_type)) = args.args.first() {
+                        return inner_type.clone();
+                    }
+
+    ty.clone()
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => {
+            if let Some(last_segment) = type_path.path.segments.last() {
+                return last_segment.ident == "Option";
+            }
+        }
+        Type::Reference(type_reference) => {
+            if let Type::Path(type_path) = type_reference.elem.as_ref()
+                && let Some(last_segment) = type_path.path.segments.last() {
+                    return last_segment.ident == "Option";
+                }
+        }
+        _ => {}
+    };
+    false
+}
+
+fn infer_inner_type_for_pattern_match(
+    ty: &Type,
+    pattern_match: &syn::Path,
+) -> syn::Result<Type> {
+    let error = || {
+        Err(syn::Error::new_spanned(
+            pattern_match,
+            "Anonymous pattern deconstructing is not implemented for this type. Add a type definition for the inner e.g. `EnumName::Branch(field: Type)`",
+        ))
+    };
+    let is_ref;
+    let ty2 = if let syn::Type::Reference(ref_ty) = ty {
+        is_ref = true;
+        &*ref_ty.elem
+    } else {
+        is_ref = false;
+        ty
+    };
+    let inner_type: &syn::Type = if let syn::Type::Path(ty) = ty2 {
+        let ty_last_segment = &ty.path.segments.last().unwrap();
+        let ty_last_segment_name = ty_last_segment.ident.to_string();
+        match ty_last_segment_name.as_str() {
+            "Result" => {
+                let arguments = &ty.path.segments.last().unwrap().arguments;
+                match arguments {
+                    syn::PathArguments::AngleBracketed(generic_arguments) => {
+                        let mut args = generic_arguments.args.iter();
+                        let ok = args.next().unwrap();
+                        let Some(err) = args.next() else {
+                            return error();
+                        };
+                        let is_ok = pattern_match
+                            .segments
+                            .last()
+                            .unwrap()
+                            .ident
+                            .to_string()
                             .as_str()
                             == "Ok";
                         let type_to_use = if is_ok { ok } else { err };
@@ -547,7 +1836,7 @@ fn infer_inner_type_for_pattern_match<'a>(
     if is_ref {
         if let syn::Type::Reference(ref_ty) = ty {
             Ok(syn::Type::Reference(syn::TypeReference {
-                and_token: ref_ty.and_token.clone(),
+                and_token: ref_ty.and_token,
                 lifetime: ref_ty.lifetime.clone(),
                 mutability: None,
                 elem: Box::new(inner_type.clone()),
@@ -559,3 +1848,649 @@ fn infer_inner_type_for_pattern_match<'a>(
         Ok(inner_type.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    /// Helper function to get the error message of resolving an invalid spec
+    fn resolve_error(views: proc_macro2::TokenStream, original_struct: ItemStruct) -> String {
+        let views: Views = syn::parse2(views).unwrap();
+        match resolve(
+            &original_struct,
+            &views,
+            Vec::new(),
+            VariantConfig::default(),
+            Vec::new(),
+            Vec::new(),
+        ) {
+            Ok(_) => panic!("Expected resolution to fail"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_view_builder_fallible_view() {
+        let error = resolve_error(
+            parse_quote! {
+                view_builder = bon,
+                view Ratio {
+                    Some(ratio) if *ratio <= 1.0
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    ratio: Option<f32>,
+                }
+            },
+        );
+        assert_eq!(error, "`view_builder` requires view 'Ratio' to have no patterns or validations");
+    }
+
+    #[test]
+    fn test_hash_ignore_unknown_field() {
+        let error = resolve_error(
+            parse_quote! {
+                #[view(hash_ignore = ratio)]
+                view KeywordSearch {
+                    query
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                    ratio: f32,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Field 'ratio' in `hash_ignore` is not in view 'KeywordSearch'"
+        );
+    }
+
+    #[test]
+    fn test_field_not_in_original_struct() {
+        let error = resolve_error(
+            parse_quote! {
+                view KeywordSearch {
+                    query,
+                    words_limit,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(error, "Field 'words_limit' not found in original struct");
+    }
+
+    #[test]
+    fn test_field_not_in_original_struct_suggestion() {
+        let error = resolve_error(
+            parse_quote! {
+                frag paging {
+                    ofset,
+                }
+                view Paged {
+                    ..paging,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(error, "Field 'ofset' not found in original struct, did you mean 'offset'?");
+
+        let error = resolve_error(
+            parse_quote! {
+                view Paged {
+                    limt,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(error, "Field 'limt' not found in original struct, did you mean 'limit'?");
+    }
+
+    #[test]
+    fn test_explicit_type_mismatch() {
+        let error = resolve_error(
+            parse_quote! {
+                view KeywordSearch {
+                    words_limit: String,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    words_limit: Option<usize>,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Type `String` of field 'words_limit' does not match the type `Option<usize>` of the original field"
+        );
+
+        let error = resolve_error(
+            parse_quote! {
+                view KeywordSearch {
+                    words_limit: Option<String>,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    words_limit: Option<usize>,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Type `Option<String>` of field 'words_limit' does not match the type `Option<usize>` of the original field"
+        );
+    }
+
+    #[test]
+    fn test_from_iter_without_single_collection() {
+        let error = resolve_error(
+            parse_quote! {
+                #[view(from_iter)]
+                view TagSearch {
+                    tags,
+                    history,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    tags: Vec<String>,
+                    history: std::collections::VecDeque<usize>,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "`from_iter` requires view 'TagSearch' to have exactly one owned collection field, e.g. a `Vec<T>`, found 2"
+        );
+    }
+
+    #[test]
+    fn test_borrow_reference_field() {
+        let error = resolve_error(
+            parse_quote! {
+                view Named {
+                    &name
+                }
+            },
+            parse_quote! {
+                struct Search<'a> {
+                    name: &'a str,
+                }
+            },
+        );
+        assert_eq!(error, "Field 'name' is already a reference in the original struct");
+    }
+
+    #[test]
+    fn test_borrow_in_nested_fragment() {
+        let error = resolve_error(
+            parse_quote! {
+                frag paging {
+                    &offset,
+                    limit,
+                }
+                view Paged {
+                    paging: ..paging,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Field 'offset' of fragment 'paging' is borrowed, so the fragment can not be nested"
+        );
+    }
+
+    #[test]
+    fn test_push_without_singular_name() {
+        let error = resolve_error(
+            parse_quote! {
+                view Tagged {
+                    #[view(push)]
+                    metadata
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    metadata: Vec<String>,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Cannot derive a singular name from 'metadata', name it with `push(name)`"
+        );
+    }
+
+    #[test]
+    fn test_field_order_unknown_field() {
+        let views: Views = parse_quote! {
+            view KeywordSearch {
+                query
+            }
+        };
+        let variant_config = VariantConfig {
+            field_order: vec![parse_quote!(limit)],
+            ..VariantConfig::default()
+        };
+        let original_struct = parse_quote! {
+            struct Search {
+                query: String,
+                limit: usize,
+            }
+        };
+        let error = match resolve(&original_struct, &views, Vec::new(), variant_config, Vec::new(), Vec::new()) {
+            Ok(_) => panic!("Expected resolution to fail"),
+            Err(err) => err.to_string(),
+        };
+        assert_eq!(error, "Field 'limit' in `field_order` is not in any view");
+    }
+
+    #[test]
+    fn test_transparent_multiple_fields() {
+        let error = resolve_error(
+            parse_quote! {
+                #[view(transparent)]
+                view Paging {
+                    offset,
+                    limit
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "`transparent` requires view 'Paging' to have exactly one field, found 2"
+        );
+    }
+
+    #[test]
+    fn test_getter_clash() {
+        let error = resolve_error(
+            parse_quote! {
+                view KeywordSearch {
+                    #[view(getter = limit)]
+                    offset,
+                    limit
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Fields 'offset' and 'limit' have the same getter 'limit'"
+        );
+    }
+
+    #[test]
+    fn test_tuple_element_needs_type() {
+        let error = resolve_error(
+            parse_quote! {
+                view Bounded {
+                    range.0 as start
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    range: Range,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Cannot infer the type of 'range.0', specify it with `range.0 as name: Type`"
+        );
+    }
+
+    #[test]
+    fn test_ref_type_without_ref_expr() {
+        let error = resolve_error(
+            parse_quote! {
+                view KeywordSearch {
+                    #[view(ref_type = &str)]
+                    query
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                }
+            },
+        );
+        assert_eq!(error, "`ref_type` and `ref_expr` must be given together");
+    }
+
+    #[test]
+    fn test_spread_except_unknown_field() {
+        let error = resolve_error(
+            parse_quote! {
+                frag all { offset, limit }
+                view Paged {
+                    ..all except { query }
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(error, "Field 'query' is not in fragment 'all'");
+    }
+
+    #[test]
+    fn test_prefixed_spread_with_validation() {
+        let error = resolve_error(
+            parse_quote! {
+                frag keyword { Some(query) if !query.is_empty() }
+                view KeywordSearch {
+                    ..keyword as kw_
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: Option<String>,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Fragment 'keyword' has validations, so its fields can not be prefixed"
+        );
+    }
+
+    #[test]
+    fn test_fragment_not_found_suggestion() {
+        let original_struct: ItemStruct = parse_quote! {
+            struct Search {
+                query: String,
+                vector: Vec<u8>,
+            }
+        };
+        let error = resolve_error(
+            parse_quote! {
+                frag keyword { query }
+                frag semantic { vector }
+                view SemanticSearch {
+                    ..semantc
+                }
+            },
+            original_struct.clone(),
+        );
+        assert_eq!(error, "Fragment 'semantc' not found, did you mean 'semantic'?");
+
+        let error = resolve_error(
+            parse_quote! {
+                frag keyword { query }
+                frag semantic { vector }
+                view SemanticSearch {
+                    ..all
+                }
+            },
+            original_struct,
+        );
+        assert_eq!(
+            error,
+            "Fragment 'all' not found, available fragments are 'keyword', 'semantic'"
+        );
+    }
+
+    #[test]
+    fn test_merge_from_without_owned_fields() {
+        let error = resolve_error(
+            parse_quote! {
+                #[view(merge_from)]
+                view KeywordSearch {
+                    Some(query),
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: Option<String>,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "`merge_from` requires view 'KeywordSearch' to have an owned field without a pattern"
+        );
+    }
+
+    #[test]
+    fn test_assert_no_overlap() {
+        let error = resolve_error(
+            parse_quote! {
+                assert_no_overlap,
+                frag all {
+                    offset,
+                }
+                view KeywordSearch {
+                    ..all,
+                    query,
+                }
+                view SemanticSearch {
+                    ..all,
+                    vector,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                    vector: Vec<u8>,
+                    offset: usize,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Field 'offset' is in views 'KeywordSearch' and 'SemanticSearch', but `assert_no_overlap` is set"
+        );
+    }
+
+    #[test]
+    fn test_and_mut_field_in_view() {
+        let error = resolve_error(
+            parse_quote! {
+                #[view(and_mut = query)]
+                view KeywordSearch {
+                    query,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                    dirty: bool,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Field 'query' in `and_mut` is in view 'KeywordSearch', so can not be borrowed mutably alongside it"
+        );
+    }
+
+    #[test]
+    fn test_into_with_without_into() {
+        let error = resolve_error(
+            parse_quote! {
+                #[view(into_with = "QueryRequest::from_view")]
+                view KeywordSearch {
+                    query,
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    query: String,
+                }
+            },
+        );
+        assert_eq!(error, "`into_with` requires `into` on view 'KeywordSearch'");
+    }
+
+    #[test]
+    fn test_covers_original() {
+        let views: Views = parse_quote! {
+            frag all {
+                offset,
+                limit,
+            }
+            view Full {
+                ..all,
+                query,
+            }
+            view Partial {
+                ..all,
+            }
+            view Extracted {
+                ..all,
+                Some(query),
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            struct Search {
+                query: Option<String>,
+                offset: usize,
+                limit: usize,
+            }
+        };
+        let builder = resolve(
+            &original_struct,
+            &views,
+            Vec::new(),
+            VariantConfig::default(),
+            Vec::new(),
+            Vec::new(),
+        )
+        .unwrap();
+        let covers = builder
+            .view_structs
+            .iter()
+            .map(|e| e.covers_original(&original_struct))
+            .collect::<Vec<_>>();
+        assert_eq!(covers, vec![true, false, false]);
+    }
+
+    #[test]
+    fn test_fragment_spread_cycle() {
+        let error = resolve_error(
+            parse_quote! {
+                frag base { offset, ..paging }
+                frag paging { ..base, limit }
+                view Paged {
+                    ..paging
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                    limit: usize,
+                }
+            },
+        );
+        assert_eq!(error, "Fragment 'base' spreads itself through 'base -> paging -> base'");
+    }
+
+    #[test]
+    fn test_fragment_spread_duplicate_field() {
+        let error = resolve_error(
+            parse_quote! {
+                frag base { offset }
+                frag paging { offset, ..base }
+                view Paged {
+                    ..paging
+                }
+            },
+            parse_quote! {
+                struct Search {
+                    offset: usize,
+                }
+            },
+        );
+        assert_eq!(
+            error,
+            "Duplicate field name 'offset' in fragment 'paging', through the spread of 'base'"
+        );
+    }
+
+    #[test]
+    fn test_used_generics() {
+        let views: Views = parse_quote! {
+            view Payload {
+                payload,
+            }
+            view Meta {
+                meta,
+            }
+        };
+        let original_struct: ItemStruct = parse_quote! {
+            struct Wrapper<'a, T: Into<U>, U>
+            where
+                U: Clone,
+            {
+                payload: &'a T,
+                meta: usize,
+            }
+        };
+        let builder = resolve(
+            &original_struct,
+            &views,
+            Vec::new(),
+            VariantConfig::default(),
+            Vec::new(),
+            Vec::new(),
+        )
+        .unwrap();
+        let generics = builder
+            .view_structs
+            .iter()
+            .map(|e| {
+                e.get_regular_generics().map(|generics| {
+                    let where_clause = &generics.where_clause;
+                    quote! { #generics #where_clause }.to_string()
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            generics,
+            vec![Some("< 'a , T : Into < U > , U > where U : Clone".to_owned()), None]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 mod simple {
     use view_types::views;
 
@@ -42,6 +43,7 @@ mod simple {
     }
 }
 
+#[allow(dead_code)]
 mod variant_testing {
     use view_types::views;
 
@@ -96,22 +98,22 @@ mod variant_testing {
     fn test() {
         let opt = Some("test".to_string());
         let bind1 = "1".to_string();
-        let mut opt_ref = Some(&bind1);
+        let opt_ref = Some(&bind1);
         let mut bind2 = "2".to_string();
-        let mut opt_mut = Some(&mut bind2);
+        let opt_mut = Some(&mut bind2);
         let bind4 = Some("4".to_string());
         let ref_opt = &bind4;
         let mut bind3 = Some("3".to_string());
-        let mut mut_opt = &mut bind3;
+        let mut_opt = &mut bind3;
         let bind5 = "5".to_string();
         let mut bind6 = Some(&bind5);
-        let mut mut_opt_ref = &mut bind6;
+        let mut_opt_ref = &mut bind6;
         let ref_opt_ref = &opt_ref;
         let mut bind8 = "8".to_owned();
         let mut bind9 = Some(&mut bind8);
         let mut_opt_mut = &mut bind9;
         let mut bind7 = "7".to_owned();
-        let mut ref_opt_mut = &Some(&mut bind7);
+        let ref_opt_mut = &Some(&mut bind7);
 
         let option_test = OptionTest {
             opt,
@@ -135,6 +137,7 @@ mod variant_testing {
     }
 }
 
+#[allow(dead_code)]
 mod complex {
     use view_types::views;
 
@@ -284,6 +287,7 @@ mod complex {
     }
 }
 
+#[allow(dead_code, private_interfaces)]
 mod builder {
     use view_types::views;
 
@@ -379,3 +383,3905 @@ mod builder {
         assert!(search.as_hybrid_search().is_none());
     }
 }
+
+#[allow(dead_code)]
+mod view_builder {
+    use view_types::views;
+
+    #[views(
+        view_builder = bon,
+        frag all {
+            offset,
+            limit,
+        }
+        pub view KeywordSearch {
+            ..all,
+            query,
+            words_limit,
+        }
+        pub view Paged {
+            ..all,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+        words_limit: Option<usize>,
+    }
+
+    #[test]
+    fn test() {
+        let keyword = KeywordSearch::builder()
+            .query("test".to_owned())
+            .offset(1)
+            .limit(10)
+            .words_limit(5)
+            .build();
+        assert_eq!(keyword.query, "test");
+        assert_eq!(keyword.offset, 1);
+        assert_eq!(keyword.limit, 10);
+        assert_eq!(keyword.words_limit, Some(5));
+
+        let keyword = KeywordSearch::builder()
+            .query("test".to_owned())
+            .offset(1)
+            .limit(10)
+            .build();
+        assert_eq!(keyword.words_limit, None);
+    }
+}
+
+#[allow(dead_code)]
+mod variant_predicates {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        pub view KeywordSearch {
+            ..all,
+            Some(query),
+        }
+        pub view HybridSearch {
+            ..all,
+            Some(query),
+            Some(ratio),
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let variants = [
+            SearchVariant::KeywordSearch(KeywordSearch {
+                offset: 0,
+                limit: 10,
+                query: "keyword".to_owned(),
+            }),
+            SearchVariant::HybridSearch(HybridSearch {
+                offset: 0,
+                limit: 10,
+                query: "hybrid".to_owned(),
+                ratio: 0.5,
+            }),
+            SearchVariant::HybridSearch(HybridSearch {
+                offset: 5,
+                limit: 10,
+                query: "hybrid2".to_owned(),
+                ratio: 0.1,
+            }),
+        ];
+
+        assert!(variants[0].is_keyword_search());
+        assert!(!variants[0].is_hybrid_search());
+        assert!(variants.iter().any(SearchVariant::is_hybrid_search));
+        for variant in &variants {
+            let predicates = [variant.is_keyword_search(), variant.is_hybrid_search()];
+            assert_eq!(predicates.iter().filter(|e| **e).count(), 1);
+        }
+        let hybrid_queries = variants
+            .iter()
+            .filter(|e| e.is_hybrid_search())
+            .map(|e| e.query().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(hybrid_queries, vec!["hybrid", "hybrid2"]);
+    }
+}
+
+#[allow(dead_code)]
+mod view_validation {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        #[derive(Debug)]
+        pub view Paged {
+            ..all,
+        } where valid = offset <= limit
+        pub view HybridSearch<'a> {
+            ..all,
+            Some(ratio),
+            vector,
+        } where valid = *ratio <= 1.0 && vector.len() <= *limit
+    )]
+    pub struct Search<'a> {
+        offset: usize,
+        limit: usize,
+        ratio: Option<f32>,
+        vector: &'a Vec<u8>,
+    }
+
+    #[test]
+    fn test() {
+        let vector = vec![0u8; 4];
+        let mut search = Search {
+            offset: 0,
+            limit: 10,
+            ratio: Some(0.5),
+            vector: &vector,
+        };
+
+        assert!(search.as_paged().unwrap().validate());
+        assert!(search.as_hybrid_search().unwrap().validate());
+        let hybrid_mut = search.as_hybrid_search_mut().unwrap();
+        *hybrid_mut.ratio = 2.0;
+        assert!(!hybrid_mut.validate());
+        assert!(search.as_hybrid_search().is_none());
+        assert!(search.as_hybrid_search_mut().is_none());
+
+        let mut paged = search.into_paged().unwrap();
+        assert!(paged.validate());
+        paged.offset = 20;
+        assert!(!paged.validate());
+
+        let search = Search {
+            offset: 20,
+            limit: 10,
+            ratio: Some(0.5),
+            vector: &vector,
+        };
+        assert!(search.as_paged().is_none());
+        assert!(search.into_paged().is_none());
+    }
+}
+
+mod include {
+    use view_types::views;
+
+    #[views(
+        include = "views/search_views.rs.in",
+        pub view Paged {
+            ..all,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: Some("test".to_owned()),
+            offset: 0,
+            limit: 10,
+        };
+        let paged = search.as_paged();
+        assert_eq!(paged.limit, &10);
+        let keyword = search.into_keyword_search().unwrap();
+        assert_eq!(keyword.query, "test");
+        assert_eq!(keyword.offset, 0);
+    }
+}
+
+mod expect {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: Some("test".to_owned()),
+            limit: 10,
+        };
+        let keyword = search.expect_keyword_search();
+        assert_eq!(keyword.query, "test");
+        assert_eq!(keyword.limit, 10);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`Search` does not satisfy the patterns and validations of `KeywordSearch`"
+    )]
+    fn test_panic() {
+        let search = Search {
+            query: None,
+            limit: 10,
+        };
+        search.expect_keyword_search();
+    }
+}
+
+#[allow(dead_code)]
+mod original_lifetime_collision {
+    use view_types::views;
+
+    #[views(
+        pub view Borrowed<'original> {
+            name,
+            count,
+        }
+    )]
+    pub struct Holder<'original> {
+        name: &'original str,
+        count: usize,
+        other: bool,
+    }
+
+    #[test]
+    fn test() {
+        let mut holder = Holder {
+            name: "holder",
+            count: 1,
+            other: true,
+        };
+        let borrowed: BorrowedRef<'_, '_> = holder.as_borrowed();
+        assert_eq!(borrowed.name, "holder");
+        assert_eq!(borrowed.count, &1);
+        let borrowed = holder.as_borrowed_mut();
+        *borrowed.count += 1;
+        assert_eq!(holder.count, 2);
+
+        let mut borrowed = holder.into_borrowed();
+        let borrowed_mut = borrowed.as_mut();
+        *borrowed_mut.count += 1;
+        assert_eq!(borrowed.as_ref().count, &3);
+    }
+}
+
+#[allow(dead_code)]
+mod hash_ignore {
+    use std::collections::HashSet;
+    use view_types::views;
+
+    #[views(
+        #[view(hash_ignore = ratio)]
+        pub view HybridSearch {
+            query,
+            limit,
+            ratio,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        limit: usize,
+        ratio: f32,
+    }
+
+    #[test]
+    fn test() {
+        let mut set = HashSet::new();
+        set.insert(HybridSearch {
+            query: "test".to_owned(),
+            limit: 10,
+            ratio: 0.5,
+        });
+        set.insert(HybridSearch {
+            query: "test".to_owned(),
+            limit: 10,
+            ratio: 0.7,
+        });
+        assert_eq!(set.len(), 1);
+        set.insert(HybridSearch {
+            query: "test".to_owned(),
+            limit: 5,
+            ratio: 0.5,
+        });
+        assert_eq!(set.len(), 2);
+    }
+}
+
+#[allow(dead_code)]
+mod str_ref {
+    use std::path::{Path, PathBuf};
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            #[view(str_ref)]
+            Some(query),
+            #[view(str_ref)]
+            path,
+            #[view(str_ref)]
+            tags,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        path: PathBuf,
+        tags: Vec<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            path: PathBuf::from("/search"),
+            tags: vec!["tag".to_owned()],
+            limit: 10,
+        };
+        let keyword = search.as_keyword_search().unwrap();
+        let query: &str = keyword.query;
+        let path: &Path = keyword.path;
+        let tags: &[String] = keyword.tags;
+        assert_eq!(query, "test");
+        assert_eq!(path, Path::new("/search"));
+        assert_eq!(tags, ["tag".to_owned()]);
+        assert_eq!(keyword.limit, &10);
+
+        let keyword = search.as_keyword_search_mut().unwrap();
+        keyword.query.push_str("ing");
+        assert_eq!(search.query.as_deref(), Some("testing"));
+
+        let mut keyword = search.into_keyword_search().unwrap();
+        let keyword_ref = keyword.as_ref();
+        let query: &str = keyword_ref.query;
+        assert_eq!(query, "testing");
+        let keyword_mut = keyword.as_mut();
+        keyword_mut.path.push("nested");
+        assert_eq!(keyword.path, PathBuf::from("/search/nested"));
+    }
+}
+
+mod debug_assert_invariants {
+    use view_types::views;
+
+    fn validate_ratio(ratio: &f32) -> bool {
+        *ratio >= 0.0 && *ratio <= 1.0
+    }
+
+    #[views(
+        debug_assert_invariants,
+        pub view HybridSearch {
+            limit,
+            Some(ratio) if validate_ratio(ratio),
+        }
+        pub view Paged {
+            offset,
+            limit,
+        } where valid = offset <= limit
+        pub view KeywordSearch {
+            limit,
+        }
+    )]
+    pub struct Search {
+        offset: usize,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            offset: 0,
+            limit: 10,
+            ratio: Some(0.5),
+        };
+        let mut hybrid: HybridSearchMutGuard<'_> = search.as_hybrid_search_mut().unwrap();
+        *hybrid.ratio = 0.7;
+        *hybrid.limit = 20;
+        drop(hybrid);
+        assert_eq!(search.ratio, Some(0.7));
+
+        let mut paged = search.as_paged_mut().unwrap();
+        *paged.offset = 5;
+        drop(paged);
+
+        let keyword: KeywordSearchMut<'_> = search.as_keyword_search_mut();
+        *keyword.limit = 1;
+        assert_eq!(search.limit, 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Invariant of `HybridSearch` violated after mutation")]
+    fn test_field_violation() {
+        let mut search = Search {
+            offset: 0,
+            limit: 10,
+            ratio: Some(0.5),
+        };
+        let mut hybrid = search.as_hybrid_search_mut().unwrap();
+        *hybrid.ratio = 2.0;
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Invariant of `Paged` violated after mutation")]
+    fn test_view_violation() {
+        let mut search = Search {
+            offset: 0,
+            limit: 10,
+            ratio: Some(0.5),
+        };
+        let mut paged = search.as_paged_mut().unwrap();
+        *paged.offset = 20;
+    }
+}
+
+#[allow(dead_code)]
+mod shared_fragments {
+    mod library {
+        view_types::fragments! {
+            paging {
+                offset,
+                limit,
+            }
+            frag keyword {
+                Some(query),
+            }
+        }
+    }
+
+    use library::{keyword, paging};
+    use view_types::views;
+
+    #[views(
+        use = paging,
+        use = keyword,
+        pub view Paged {
+            ..paging,
+        }
+        pub view KeywordSearch {
+            ..paging,
+            ..keyword,
+        }
+    )]
+    #[derive(Debug)]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[views(
+        use = library::paging,
+        pub view FilterPage {
+            ..paging,
+            tag,
+        }
+    )]
+    pub struct Filter {
+        tag: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: Some("test".to_owned()),
+            offset: 0,
+            limit: 10,
+        };
+        assert_eq!(search.as_paged().limit, &10);
+        let keyword = search.into_keyword_search().unwrap();
+        assert_eq!(keyword.query, "test");
+
+        let filter = Filter {
+            tag: "tag".to_owned(),
+            offset: 5,
+            limit: 10,
+        };
+        let page = filter.into_filter_page();
+        assert_eq!(page.offset, 5);
+        assert_eq!(page.tag, "tag");
+    }
+}
+
+#[allow(dead_code)]
+mod push {
+    use view_types::views;
+
+    #[views(
+        pub view Tagged {
+            query,
+            #[view(push)]
+            tags,
+            #[view(push(entry))]
+            history,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        tags: Vec<String>,
+        history: Vec<usize>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: "test".to_owned(),
+            tags: Vec::new(),
+            history: Vec::new(),
+        };
+        let mut tagged = search.as_tagged_mut();
+        tagged.push_tag("rust".to_owned());
+        tagged.push_entry(1);
+        assert_eq!(search.tags, ["rust"]);
+        assert_eq!(search.history, [1]);
+
+        let mut tagged = search.into_tagged();
+        tagged.as_mut().push_tag("macros".to_owned());
+        assert_eq!(tagged.tags, ["rust", "macros"]);
+    }
+}
+
+#[allow(dead_code)]
+mod view_default {
+    use view_types::views;
+
+    #[views(
+        #[view(new)]
+        pub view KeywordSearch {
+            query,
+            offset,
+            limit,
+        }
+    )]
+    #[view_default(offset = 0, limit = 10)]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let keyword = KeywordSearch::new("test".to_owned());
+        assert_eq!(keyword.query, "test");
+        assert_eq!(keyword.offset, 0);
+        assert_eq!(keyword.limit, 10);
+    }
+}
+
+#[allow(dead_code)]
+mod debug_transparent {
+    use view_types::views;
+
+    #[views(
+        #[derive(Debug)]
+        pub view KeywordSearch {
+            query,
+            limit,
+        }
+        #[derive(Debug)]
+        pub view Paged {
+            limit,
+        }
+    )]
+    #[Variant(debug_transparent)]
+    pub struct Search {
+        query: String,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: "test".to_owned(),
+            limit: 10,
+        };
+        let keyword = search.into_keyword_search();
+        let expected = format!("{keyword:?}");
+        let variant = SearchVariant::KeywordSearch(keyword);
+        assert_eq!(format!("{variant:?}"), expected);
+        assert_eq!(
+            format!("{:?}", SearchVariant::Paged(Paged { limit: 5 })),
+            "Paged { limit: 5 }"
+        );
+    }
+}
+
+#[allow(dead_code)]
+mod field_order {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+            offset,
+            limit,
+        }
+        pub view Paged {
+            offset,
+            limit,
+        }
+    )]
+    #[Variant(field_order(limit, offset))]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let variant = SearchVariant::Paged(Paged {
+            offset: 1,
+            limit: 10,
+        });
+        assert_eq!(variant.limit(), &10);
+        assert_eq!(variant.offset(), &1);
+        assert_eq!(variant.query(), None);
+    }
+}
+
+#[allow(dead_code)]
+mod try_from_variant {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+        }
+        pub view SemanticSearch<'a> {
+            vector,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        vector: Option<&'a [u8]>,
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2, 3];
+        let variant = SearchVariant::SemanticSearch(SemanticSearch {
+            vector: Some(&vector),
+        });
+        let variant = match KeywordSearch::try_from(variant) {
+            Ok(_) => panic!("Expected the variant to not be a keyword search"),
+            Err(variant) => variant,
+        };
+        let semantic = match SemanticSearch::try_from(variant) {
+            Ok(semantic) => semantic,
+            Err(_) => panic!("Expected the variant to be a semantic search"),
+        };
+        assert_eq!(semantic.vector, Some(&vector[..]));
+    }
+}
+
+#[allow(dead_code)]
+mod fragment_validation {
+    use view_types::views;
+
+    #[views(
+        frag semantic {
+            vector,
+            dim,
+        } where valid = vector.len() == *dim
+        frag paging {
+            offset,
+            limit,
+        } where valid = offset <= limit
+        pub view SemanticSearch {
+            ..semantic,
+        }
+        pub view PagedSemanticSearch {
+            ..semantic,
+            ..paging,
+        }
+    )]
+    pub struct Search {
+        vector: Vec<u8>,
+        dim: usize,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            vector: vec![1, 2, 3],
+            dim: 3,
+            offset: 20,
+            limit: 10,
+        };
+        assert!(search.as_semantic_search().is_some());
+        assert!(search.as_paged_semantic_search().is_none());
+
+        search.offset = 0;
+        assert!(search.as_paged_semantic_search().is_some());
+
+        search.dim = 4;
+        assert!(search.as_semantic_search().is_none());
+        assert!(search.into_paged_semantic_search().is_none());
+    }
+}
+
+mod snapshot {
+    use view_types::views;
+
+    #[views(
+        #[view(snapshot)]
+        pub view Paging {
+            offset,
+            Some(limit),
+        }
+    )]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: Option<usize>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: "test".to_owned(),
+            offset: 0,
+            limit: Some(10),
+        };
+        let snapshot = search.paging_snapshot().unwrap();
+        search.offset = 5;
+        search.query.push_str("ing");
+        assert_eq!(snapshot.offset, 0);
+        assert_eq!(snapshot.limit, 10);
+        assert_eq!(search.query, "testing");
+
+        search.limit = None;
+        assert!(search.paging_snapshot().is_none());
+    }
+}
+
+#[allow(dead_code)]
+mod visitor {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+        }
+        pub view SemanticSearch<'a> {
+            vector,
+        }
+    )]
+    #[Variant(visitor)]
+    pub struct Search<'a> {
+        query: String,
+        vector: Option<&'a [u8]>,
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        keyword: Vec<String>,
+        semantic: usize,
+    }
+
+    impl<'a> SearchVariantVisitor<'a> for Counter {
+        fn visit_keyword_search(&mut self, view: KeywordSearch) {
+            self.keyword.push(view.query);
+        }
+
+        fn visit_semantic_search(&mut self, view: SemanticSearch<'a>) {
+            self.semantic += view.vector.map_or(0, |e| e.len());
+        }
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2, 3];
+        let mut counter = Counter::default();
+        SearchVariant::KeywordSearch(KeywordSearch {
+            query: "test".to_owned(),
+        })
+        .accept(&mut counter);
+        SearchVariant::SemanticSearch(SemanticSearch {
+            vector: Some(&vector),
+        }).accept(&mut counter);
+        assert_eq!(counter.keyword, ["test"]);
+        assert_eq!(counter.semantic, 3);
+    }
+}
+
+#[allow(dead_code)]
+mod boxed_trait_object {
+    use view_types::views;
+
+    #[views(
+        pub view Handled {
+            handler,
+        }
+        pub view Required {
+            Some(handler),
+        }
+        pub view Named {
+            name,
+        }
+    )]
+    pub struct Callback {
+        name: String,
+        handler: Option<Box<dyn Fn() -> usize + Send>>,
+    }
+
+    #[test]
+    fn test() {
+        let callback = Callback {
+            name: "callback".to_owned(),
+            handler: Some(Box::new(|| 1)),
+        };
+        let variant = CallbackVariant::Handled(callback.into_handled());
+        let handler: Option<&(dyn Fn() -> usize + Send)> = variant.handler();
+        assert_eq!(handler.unwrap()(), 1);
+
+        let variant = CallbackVariant::Required(Required {
+            handler: Box::new(|| 2),
+        });
+        assert_eq!(variant.handler().unwrap()(), 2);
+
+        let variant = CallbackVariant::Named(Named {
+            name: "named".to_owned(),
+        });
+        assert!(variant.handler().is_none());
+    }
+}
+
+#[allow(dead_code)]
+mod take_replace {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            limit: 10,
+        };
+        let mut keyword = search.as_keyword_search_mut();
+        assert_eq!(keyword.replace_query("rust".to_owned()).as_deref(), Some("test"));
+        assert_eq!(keyword.take_query().as_deref(), Some("rust"));
+        assert_eq!(keyword.take_query(), None);
+        assert_eq!(search.query, None);
+    }
+}
+
+#[allow(dead_code)]
+mod transparent {
+    use std::mem::{align_of, size_of};
+    use view_types::views;
+
+    #[views(
+        #[view(transparent)]
+        pub view Ratio {
+            ratio,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        ratio: f32,
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(size_of::<Ratio>(), size_of::<f32>());
+        assert_eq!(align_of::<Ratio>(), align_of::<f32>());
+
+        let search = Search {
+            query: "test".to_owned(),
+            ratio: 0.5,
+        };
+        let ratio = search.into_ratio();
+        let inner: f32 = unsafe { std::mem::transmute(ratio) };
+        assert_eq!(inner, 0.5);
+    }
+}
+
+#[allow(dead_code)]
+mod from_tuple {
+    use view_types::views;
+
+    #[views(
+        #[derive(Debug, PartialEq)]
+        pub view KeywordSearch {
+            query,
+            offset,
+            limit,
+        }
+        #[derive(Debug, PartialEq)]
+        pub view Paging {
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let searches: Vec<KeywordSearch> = vec![("test".to_owned(), 0, 10), ("rust".to_owned(), 10, 20)]
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        assert_eq!(searches[1].query, "rust");
+        assert_eq!(searches[1].offset, 10);
+        assert_eq!(searches[1].limit, 20);
+        assert_eq!(Paging::from((5,)), Paging { limit: 5 });
+    }
+}
+
+mod getter {
+    use view_types::views;
+
+    #[views(
+        frag keyword {
+            #[view(getter = query_text)]
+            query,
+        }
+        pub view KeywordSearch {
+            ..keyword,
+            limit,
+        }
+        pub view Paging {
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: "test".to_owned(),
+            limit: 10,
+        };
+        let variant = SearchVariant::KeywordSearch(search.into_keyword_search());
+        assert_eq!(variant.query_text().map(String::as_str), Some("test"));
+        assert_eq!(variant.limit(), &10);
+    }
+}
+
+mod as_slice {
+    use view_types::views;
+
+    #[views(
+        pub view Buffered {
+            #[view(as_slice)]
+            buf,
+            #[view(as_slice)]
+            Some(header) if header[0] == 1,
+        }
+    )]
+    pub struct Packet {
+        buf: [u8; 4],
+        header: Option<[u8; 2]>,
+    }
+
+    #[test]
+    fn test() {
+        let mut packet = Packet {
+            buf: [1, 2, 3, 4],
+            header: Some([1, 0]),
+        };
+        let buffered = packet.as_buffered().unwrap();
+        let buf: &[u8] = buffered.buf;
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(buffered.header, [1, 0]);
+
+        let buffered = packet.as_buffered_mut().unwrap();
+        let buf: &mut [u8] = buffered.buf;
+        buf[0] = 5;
+        assert_eq!(packet.buf, [5, 2, 3, 4]);
+
+        let mut buffered = packet.into_buffered().unwrap();
+        let buffered = buffered.as_mut();
+        buffered.header[1] = 7;
+    }
+}
+
+mod mut_as_ref {
+    use view_types::views;
+
+    #[views(
+        pub view HybridSearch<'a> {
+            #[view(str_ref)]
+            Some(query),
+            limit,
+            vector,
+            cursor,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        limit: usize,
+        vector: &'a [u8],
+        cursor: &'a mut usize,
+    }
+
+    fn total(view: HybridSearchRef<'_, '_>) -> usize {
+        view.query.len() + view.limit + view.vector.len() + view.cursor
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2];
+        let mut cursor = 3;
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            limit: 10,
+            vector: &vector,
+            cursor: &mut cursor,
+        };
+        let hybrid = search.as_hybrid_search_mut().unwrap();
+        *hybrid.limit += 1;
+        assert_eq!(total(hybrid.as_ref()), 4 + 11 + 2 + 3);
+        *hybrid.cursor = 0;
+        assert_eq!(total(hybrid.as_ref()), 4 + 11 + 2);
+    }
+}
+
+#[allow(dead_code)]
+mod tuple_element {
+    use view_types::views;
+
+    type Range = (usize, usize);
+
+    #[views(
+        frag bounds {
+            range.0 as start: usize,
+            range.1 as end: usize,
+        }
+        pub view Named {
+            pair.0 as low if *low < 10,
+            pair.1 as name,
+        }
+        pub view Bounded {
+            ..bounds,
+            pair.0 as low,
+        }
+    )]
+    pub struct Search {
+        pair: (usize, String),
+        range: Range,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            pair: (1, "test".to_owned()),
+            range: (2, 5),
+        };
+        let named = search.as_named().unwrap();
+        assert_eq!(*named.low, 1);
+        assert_eq!(named.name, "test");
+
+        let bounded = search.as_bounded_mut();
+        *bounded.end = 6;
+        *bounded.low = 20;
+        assert_eq!(search.range, (2, 6));
+        assert!(search.as_named().is_none());
+
+        let bounded = search.into_bounded();
+        assert_eq!((bounded.start, bounded.end, bounded.low), (2, 6, 20));
+    }
+}
+
+#[allow(dead_code)]
+mod mut_field_accessor {
+    use view_types::views;
+
+    #[views(
+        pub view Counter<'a> {
+            query,
+            mut_number,
+            tag,
+        }
+        pub view Query {
+            query,
+        }
+        pub view Both<'a> {
+            Some(mut_option),
+            mut_number,
+            tag,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        mut_number: &'a mut usize,
+        mut_option: Option<&'a mut usize>,
+        tag: Option<&'a str>,
+    }
+
+    #[test]
+    fn test() {
+        let mut number = 1;
+        let variant = SearchVariant::Counter(Counter {
+            query: "test".to_owned(),
+            mut_number: &mut number,
+            tag: None,
+        });
+        let mut_number: Option<&usize> = variant.mut_number();
+        assert_eq!(mut_number, Some(&1));
+
+        let variant = SearchVariant::Query(Query {
+            query: "test".to_owned(),
+        });
+        assert_eq!(variant.mut_number(), None);
+
+        let mut other = 2;
+        let mut number = 3;
+        let variant = SearchVariant::Both(Both {
+            mut_option: &mut other,
+            mut_number: &mut number,
+            tag: Some("tag"),
+        });
+        let mut_option: Option<&usize> = variant.mut_option();
+        assert_eq!(mut_option, Some(&2));
+        assert_eq!(variant.mut_number(), Some(&3));
+    }
+}
+
+#[allow(dead_code)]
+mod field_set {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+        pub view Paging {
+            offset,
+            limit,
+        }
+    )]
+    #[Variant(field_set)]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let variant = SearchVariant::KeywordSearch(KeywordSearch {
+            query: "test".to_owned(),
+            limit: 10,
+        });
+        let fields = variant.fields_present();
+        assert!(fields.contains(SearchFieldSet::QUERY));
+        assert!(fields.contains(SearchFieldSet::QUERY | SearchFieldSet::LIMIT));
+        assert!(!fields.contains(SearchFieldSet::OFFSET));
+
+        let variant = SearchVariant::Paging(Paging { offset: 0, limit: 10 });
+        let fields = variant.fields_present();
+        assert_eq!(fields, SearchFieldSet::OFFSET | SearchFieldSet::LIMIT);
+        assert!(!fields.contains(SearchFieldSet::QUERY));
+    }
+}
+
+mod cow_mut {
+    use std::borrow::Cow;
+    use view_types::views;
+
+    #[views(
+        #[view(cow_mut)]
+        pub view KeywordSearch {
+            #[view(str_ref)]
+            Some(query),
+            tags,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: Some("test".to_owned()),
+            tags: vec!["rust".to_owned()],
+        };
+        let mut keyword = search.as_keyword_search_cow_mut().unwrap();
+        assert!(matches!(keyword.query, Cow::Borrowed("test")));
+        assert!(matches!(keyword.tags, Cow::Borrowed(_)));
+
+        keyword.to_mut_tags().push("macros".to_owned());
+        assert!(matches!(keyword.query, Cow::Borrowed(_)));
+        assert!(matches!(keyword.tags, Cow::Owned(_)));
+        assert_eq!(*keyword.tags, ["rust", "macros"]);
+        assert_eq!(search.tags, ["rust"]);
+    }
+}
+
+#[allow(dead_code)]
+mod merge_from {
+    use view_types::views;
+
+    #[views(
+        #[view(merge_from)]
+        pub view Paged {
+            Some(query),
+            offset,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let template = Search {
+            query: Some("template".to_owned()),
+            offset: 10,
+            limit: 20,
+        };
+        let mut search = Search {
+            query: Some("live".to_owned()),
+            offset: 0,
+            limit: 5,
+        };
+        let template = template.as_paged().unwrap();
+        let mut live = search.as_paged_mut().unwrap();
+        live.merge_from(&template);
+        assert_eq!(search.query.as_deref(), Some("live"));
+        assert_eq!(search.offset, 10);
+        assert_eq!(search.limit, 20);
+    }
+}
+
+mod boxed_trait {
+    use view_types::views;
+
+    pub trait Searchable {
+        fn describe(&self) -> String;
+    }
+
+    #[views(
+        boxed_trait = Searchable,
+        pub view KeywordSearch {
+            query,
+        }
+        pub view SemanticSearch<'a> {
+            vector,
+            limit,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        vector: &'a [u8],
+        limit: usize,
+    }
+
+    impl Searchable for KeywordSearch {
+        fn describe(&self) -> String {
+            format!("keyword {}", self.query)
+        }
+    }
+
+    impl Searchable for SemanticSearch<'_> {
+        fn describe(&self) -> String {
+            format!("semantic {:?} {}", self.vector, self.limit)
+        }
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2];
+        let searches: Vec<Box<dyn Searchable>> = vec![
+            KeywordSearch {
+                query: "test".to_owned(),
+            }
+            .into(),
+            SemanticSearch {
+                vector: &vector,
+                limit: 10,
+            }
+            .into(),
+        ];
+        let descriptions = searches.iter().map(|e| e.describe()).collect::<Vec<_>>();
+        assert_eq!(descriptions, ["keyword test", "semantic [1, 2] 10"]);
+    }
+}
+
+mod borrow_method_names {
+    use view_types::views;
+
+    #[views(
+        ref_method = "borrow",
+        mut_method = "borrow_mut",
+        pub view KeywordSearch {
+            query,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut keyword = KeywordSearch {
+            query: "test".to_owned(),
+            limit: 10,
+        };
+        let keyword_mut = keyword.borrow_mut();
+        *keyword_mut.limit = 20;
+        assert_eq!(keyword_mut.borrow().limit, &20);
+        let keyword_ref = keyword.borrow();
+        assert_eq!(keyword_ref.query, "test");
+        assert_eq!(*keyword_ref.limit, 20);
+    }
+}
+
+#[allow(dead_code)]
+mod field_index {
+    use view_types::views;
+
+    #[views(
+        #[view(field_index)]
+        pub view KeywordSearch {
+            query,
+            words_limit,
+        }
+        #[view(field_index)]
+        pub view SemanticSearch<'a> {
+            vector,
+            words_limit,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        words_limit: usize,
+        vector: &'a [u8],
+    }
+
+    #[test]
+    fn test() {
+        let keyword = KeywordSearch {
+            query: "test".to_owned(),
+            words_limit: 10,
+        };
+        assert_eq!(format!("{:?}", &keyword[SearchField::Query]), "\"test\"");
+        assert_eq!(format!("{:?}", &keyword[SearchField::WordsLimit]), "10");
+
+        let vector = [1, 2];
+        let semantic = SemanticSearch {
+            vector: &vector,
+            words_limit: 5,
+        };
+        let cells = [SearchField::Vector, SearchField::WordsLimit]
+            .into_iter()
+            .map(|field| format!("{:?}", &semantic[field]))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, ["[1, 2]", "5"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Field 'Vector' is not in view 'KeywordSearch'")]
+    fn test_missing_field() {
+        let keyword = KeywordSearch {
+            query: "test".to_owned(),
+            words_limit: 10,
+        };
+        let _ = &keyword[SearchField::Vector];
+    }
+}
+
+mod inherit_visibility {
+    mod search {
+        use view_types::views;
+
+        #[views(
+            inherit_visibility,
+            view KeywordSearch {
+                query,
+            }
+        )]
+        pub(crate) struct Search {
+            pub(crate) query: String,
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut keyword = search::KeywordSearch {
+            query: "test".to_owned(),
+        };
+        let keyword_mut: search::KeywordSearchMut = keyword.as_mut();
+        keyword_mut.query.push('s');
+        let keyword_ref: search::KeywordSearchRef = keyword.as_ref();
+        assert_eq!(keyword_ref.query, "tests");
+    }
+}
+
+#[allow(dead_code)]
+mod accessor_or {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch<'a> {
+            query,
+            tag,
+        }
+        pub view SemanticSearch {
+            limit,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        tag: &'a str,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let default_query = "default".to_owned();
+        let keyword = SearchVariant::KeywordSearch(KeywordSearch {
+            query: "test".to_owned(),
+            tag: "rust",
+        });
+        assert_eq!(keyword.query_or(&default_query), "test");
+        assert_eq!(keyword.tag_or("none"), "rust");
+        assert_eq!(*keyword.limit_or(&5), 5);
+
+        let semantic = SearchVariant::SemanticSearch(SemanticSearch { limit: 10 });
+        assert_eq!(semantic.query_or(&default_query), "default");
+        assert_eq!(semantic.tag_or("none"), "none");
+        assert_eq!(*semantic.limit_or(&5), 10);
+    }
+}
+
+#[allow(dead_code)]
+mod view_derive {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+        }
+        pub view Paged {
+            offset,
+            limit,
+        }
+    )]
+    #[ViewDerive(Debug, Clone, PartialEq)]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: "test".to_owned(),
+            offset: 0,
+            limit: 10,
+        };
+        let paged = search.into_paged();
+        assert_eq!(paged.clone(), Paged { offset: 0, limit: 10 });
+        let keyword = KeywordSearch {
+            query: "test".to_owned(),
+        };
+        assert_eq!(format!("{:?}", keyword), "KeywordSearch { query: \"test\" }");
+    }
+}
+
+#[allow(dead_code)]
+mod satisfied_views {
+    use view_types::views;
+
+    fn validate_ratio(ratio: &f32) -> bool {
+        *ratio >= 0.0 && *ratio <= 1.0
+    }
+
+    #[views(
+        pub view Paged {
+            offset,
+        }
+        pub view KeywordSearch {
+            Some(query),
+            offset,
+        }
+        pub view HybridSearch {
+            Some(query),
+            Some(ratio) if validate_ratio(ratio),
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            offset: 0,
+            ratio: Some(2.0),
+        };
+        assert_eq!(
+            search.satisfied_views(),
+            [SearchVariantKind::Paged, SearchVariantKind::KeywordSearch]
+        );
+        search.ratio = Some(0.5);
+        assert_eq!(search.satisfied_views().len(), 3);
+
+        let variant = SearchVariant::Paged(search.into_paged());
+        assert_eq!(variant.kind(), SearchVariantKind::Paged);
+    }
+}
+
+#[allow(dead_code)]
+mod sequential_mut_borrows {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        pub view KeywordSearch {
+            ..all,
+            Some(query),
+        }
+        pub view HybridSearch<'a> {
+            ..all,
+            Some(query),
+            tag,
+            vector,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        tag: &'a str,
+        vector: Vec<u8>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            offset: 0,
+            limit: 10,
+            tag: "rust",
+            vector: vec![1, 2],
+        };
+
+        let hybrid = search.as_hybrid_search_mut().unwrap();
+        hybrid.vector.push(3);
+        *hybrid.offset += 1;
+
+        let keyword = search.as_keyword_search_mut().unwrap();
+        keyword.query.push('s');
+        *keyword.offset += 1;
+
+        let hybrid = search.as_hybrid_search_mut().unwrap();
+        *hybrid.limit = 20;
+        assert_eq!(hybrid.tag, "rust");
+
+        assert_eq!(search.offset, 2);
+        assert_eq!(search.limit, 20);
+        assert_eq!(search.query.as_deref(), Some("tests"));
+        assert_eq!(search.vector, [1, 2, 3]);
+    }
+}
+
+mod into_reported {
+    use view_types::views;
+
+    fn validate_ratio(ratio: &f32) -> bool {
+        *ratio >= 0.0 && *ratio <= 1.0
+    }
+
+    #[views(
+        pub view HybridSearch {
+            Some(query),
+            Some(ratio) if validate_ratio(ratio),
+            limit,
+        } where valid = *limit > 0
+    )]
+    pub struct Search {
+        query: Option<String>,
+        ratio: Option<f32>,
+        limit: usize,
+    }
+
+    fn search(query: Option<&str>, ratio: Option<f32>, limit: usize) -> Search {
+        Search {
+            query: query.map(ToOwned::to_owned),
+            ratio,
+            limit,
+        }
+    }
+
+    #[test]
+    fn test() {
+        let (view, failed) = search(None, Some(0.5), 10).into_hybrid_search_reported();
+        assert!(view.is_none());
+        assert_eq!(failed, Some("query"));
+
+        let (view, failed) = search(Some("test"), Some(2.0), 10).into_hybrid_search_reported();
+        assert!(view.is_none());
+        assert_eq!(failed, Some("ratio"));
+
+        let (view, failed) = search(Some("test"), Some(0.5), 0).into_hybrid_search_reported();
+        assert!(view.is_none());
+        assert_eq!(failed, Some("valid"));
+
+        let (view, failed) = search(Some("test"), Some(0.5), 10).into_hybrid_search_reported();
+        assert_eq!(view.unwrap().query, "test");
+        assert_eq!(failed, None);
+    }
+}
+
+mod and_mut {
+    use view_types::views;
+
+    #[views(
+        #[view(and_mut = dirty)]
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+        #[view(and_mut(dirty, history))]
+        pub view Paged {
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+        dirty: bool,
+        history: Vec<String>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            limit: 10,
+            dirty: false,
+            history: Vec::new(),
+        };
+        let (keyword, dirty) = search.keyword_search_ref_and_dirty_mut().unwrap();
+        *dirty = keyword.query == "test" && *keyword.limit > 5;
+        assert!(search.dirty);
+
+        let (paged, history) = search.paged_ref_and_history_mut();
+        history.push(format!("limit {}", paged.limit));
+        assert_eq!(search.history, ["limit 10"]);
+
+        search.query = None;
+        assert!(search.keyword_search_ref_and_dirty_mut().is_none());
+    }
+}
+
+#[allow(dead_code)]
+mod ref_copy {
+    use view_types::views;
+
+    #[views(
+        pub view SemanticSearch<'a> {
+            vector,
+            limit,
+        }
+        #[view(no_ref_copy)]
+        pub view Paged {
+            limit,
+        }
+    )]
+    pub struct Search<'a> {
+        vector: &'a [u8],
+        limit: usize,
+    }
+
+    impl Clone for PagedRef<'_> {
+        fn clone(&self) -> Self {
+            PagedRef { limit: self.limit }
+        }
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            vector: &[1, 2],
+            limit: 10,
+        };
+        let semantic = search.as_semantic_search();
+        let copied = semantic;
+        #[allow(clippy::clone_on_copy)]
+        let cloned = semantic.clone();
+        assert_eq!(copied.vector, cloned.vector);
+        assert_eq!(*semantic.limit, 10);
+
+        let paged = search.as_paged();
+        assert_eq!(paged.clone().limit, &10);
+    }
+}
+
+#[allow(dead_code)]
+mod vtable {
+    macro_rules! search_views {
+        ($($variant_option:ident)?) => {
+            use view_types::views;
+
+            #[views(
+                pub view KeywordSearch {
+                    Some(query),
+                    offset,
+                    words_limit,
+                }
+                pub view SemanticSearch<'a> {
+                    vector,
+                    offset,
+                }
+                pub view HybridSearch<'a> {
+                    Some(query),
+                    vector,
+                    offset,
+                    words_limit,
+                }
+            )]
+            #[Variant($($variant_option)?)]
+            pub struct Search<'a> {
+                query: Option<String>,
+                offset: usize,
+                words_limit: Option<usize>,
+                vector: &'a [u8],
+            }
+
+            pub fn accessors(variant: &SearchVariant) -> (Option<String>, usize, Option<usize>, Option<Vec<u8>>) {
+                (
+                    variant.query().cloned(),
+                    *variant.offset(),
+                    variant.words_limit().copied(),
+                    variant.vector().map(|e| e.to_vec()),
+                )
+            }
+
+            pub fn variants(vector: &[u8]) -> Vec<SearchVariant<'_>> {
+                let search = || Search {
+                    query: Some("test".to_owned()),
+                    offset: 1,
+                    words_limit: Some(5),
+                    vector,
+                };
+                vec![
+                    SearchVariant::KeywordSearch(search().into_keyword_search().unwrap()),
+                    SearchVariant::SemanticSearch(search().into_semantic_search()),
+                    SearchVariant::HybridSearch(search().into_hybrid_search().unwrap()),
+                ]
+            }
+        };
+    }
+
+    mod matched {
+        search_views!();
+    }
+
+    mod dispatched {
+        search_views!(vtable);
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2];
+        let matched = matched::variants(&vector);
+        let dispatched = dispatched::variants(&vector);
+        for (matched, dispatched) in matched.iter().zip(&dispatched) {
+            assert_eq!(matched::accessors(matched), dispatched::accessors(dispatched));
+        }
+        assert_eq!(dispatched::accessors(&dispatched[1]), (None, 1, None, Some(vec![1, 2])));
+    }
+}
+
+#[allow(dead_code)]
+mod borrow_lifetime {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch<'a> {
+            query,
+            tag,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        tag: &'a str,
+        limit: usize,
+    }
+
+    fn keyword_search<'r, 'a>(search: &'r Search<'a>) -> KeywordSearchRef<'r, 'a> {
+        search.as_keyword_search()
+    }
+
+    fn keyword_search_mut<'r, 'a>(search: &'r mut Search<'a>) -> KeywordSearchMut<'r, 'a> {
+        search.as_keyword_search_mut()
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: "test".to_owned(),
+            tag: "rust",
+            limit: 10,
+        };
+        keyword_search_mut(&mut search).query.push('s');
+        let keyword = keyword_search(&search);
+        assert_eq!(keyword.query, "tests");
+        assert_eq!(keyword.tag, "rust");
+        assert_eq!(search.limit, 10);
+    }
+}
+
+mod into {
+    use view_types::views;
+
+    #[derive(Debug, PartialEq)]
+    pub struct QueryRequest {
+        query: String,
+        limit: usize,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct PageRequest {
+        range: std::ops::Range<usize>,
+    }
+
+    impl PageRequest {
+        fn from_view(view: Paged) -> Self {
+            PageRequest {
+                range: view.offset..view.offset + view.limit,
+            }
+        }
+    }
+
+    #[views(
+        #[view(into = QueryRequest)]
+        pub view KeywordSearch {
+            query,
+            limit,
+        }
+        #[view(into = PageRequest, into_with = "PageRequest::from_view")]
+        pub view Paged {
+            offset,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = || Search {
+            query: "test".to_owned(),
+            offset: 10,
+            limit: 5,
+        };
+        let request: QueryRequest = search().into_keyword_search().into();
+        assert_eq!(
+            request,
+            QueryRequest {
+                query: "test".to_owned(),
+                limit: 5
+            }
+        );
+        let request = PageRequest::from(search().into_paged());
+        assert_eq!(request, PageRequest { range: 10..15 });
+    }
+}
+
+mod downgrade {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            tags,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        tags: Vec<String>,
+        limit: usize,
+    }
+
+    fn describe(keyword: KeywordSearchRef<'_>) -> String {
+        format!("{} {:?} {}", keyword.query, keyword.tags, keyword.limit)
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            tags: vec!["rust".to_owned()],
+            limit: 10,
+        };
+        let keyword = search.as_keyword_search_mut().unwrap();
+        keyword.tags.push("macros".to_owned());
+        assert_eq!(describe(keyword.downgrade()), "test [\"rust\", \"macros\"] 10");
+        *keyword.limit = 20;
+        assert_eq!(describe(keyword.downgrade()), "test [\"rust\", \"macros\"] 20");
+    }
+}
+
+#[allow(dead_code)]
+mod nested_fragment {
+    use view_types::views;
+
+    #[views(
+        frag paging {
+            offset,
+            limit,
+        }
+        frag semantic {
+            Some(query),
+            vector,
+        }
+        #[derive(Debug, PartialEq)]
+        pub view SemanticSearch<'a> {
+            paging: ..paging,
+            ..semantic,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        vector: &'a Vec<u8>,
+    }
+
+    #[test]
+    fn test() {
+        let vector = vec![1, 2, 3];
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            offset: 5,
+            limit: 10,
+            vector: &vector,
+        };
+
+        let semantic = search.as_semantic_search().unwrap();
+        assert_eq!(*semantic.paging.offset, 5);
+        assert_eq!(*semantic.paging.limit, 10);
+
+        let semantic = search.as_semantic_search_mut().unwrap();
+        *semantic.paging.offset = 20;
+        assert_eq!(*semantic.downgrade().paging.offset, 20);
+
+        let paging = search.as_paging_view();
+        assert_eq!(*paging.limit, 10);
+
+        let semantic = search.into_semantic_search().unwrap();
+        assert_eq!(semantic.paging.offset, 20);
+        assert_eq!(
+            semantic,
+            SemanticSearch {
+                paging: PagingView {
+                    offset: 20,
+                    limit: 10,
+                },
+                query: "test".to_owned(),
+                vector: &vector,
+            }
+        );
+    }
+}
+
+#[allow(dead_code)]
+mod deref_pointer {
+    use std::ops::Deref;
+
+    use view_types::views;
+
+    pub struct Shared<T>(std::rc::Rc<T>);
+
+    impl<T> Deref for Shared<T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    #[views(
+        deref(Shared),
+        pub view KeywordSearch {
+            query,
+            Some(tags),
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Shared<String>,
+        tags: Option<Shared<Vec<String>>>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Shared(std::rc::Rc::new("test".to_owned())),
+            tags: Some(Shared(std::rc::Rc::new(vec!["rust".to_owned()]))),
+            limit: 10,
+        };
+
+        let keyword = search.as_keyword_search().unwrap();
+        let query: &String = keyword.query;
+        assert_eq!(query, "test");
+        let tags: &Vec<String> = keyword.tags;
+        assert_eq!(tags, &["rust"]);
+
+        let keyword = search.as_keyword_search_mut().unwrap();
+        *keyword.query = Shared(std::rc::Rc::new("replaced".to_owned()));
+        assert_eq!(keyword.downgrade().query, "replaced");
+    }
+}
+
+#[allow(dead_code)]
+mod common_fields {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        pub view KeywordSearch {
+            ..all,
+            query,
+        }
+        pub view SemanticSearch {
+            Some(query),
+            ..all,
+            ratio,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        ratio: f32,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: Some("test".to_owned()),
+            offset: 5,
+            limit: 10,
+            ratio: 0.5,
+        };
+        let variant = SearchVariant::SemanticSearch(search.into_semantic_search().unwrap());
+        let (query, offset, limit) = variant.common_fields();
+        assert_eq!(query.map(String::as_str), Some("test"));
+        assert_eq!(*offset, 5);
+        assert_eq!(*limit, 10);
+
+        let variant = SearchVariant::KeywordSearch(KeywordSearch {
+            query: None,
+            offset: 1,
+            limit: 2,
+        });
+        let (query, offset, limit) = variant.common_fields();
+        assert_eq!(query, None);
+        assert_eq!((*offset, *limit), (1, 2));
+    }
+}
+
+mod inherit_derives {
+    use view_types::views;
+
+    #[views(
+        inherit_derives,
+        pub view KeywordSearch {
+            query,
+            limit,
+        }
+        pub view Paged {
+            offset,
+            limit,
+        }
+    )]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: "test".to_owned(),
+            offset: 5,
+            limit: 10,
+        };
+        assert_eq!(search.clone(), search);
+
+        let keyword = search.clone().into_keyword_search();
+        assert_eq!(keyword.clone(), keyword);
+        assert_eq!(format!("{keyword:?}"), "KeywordSearch { query: \"test\", limit: 10 }");
+
+        let paged = search.into_paged();
+        assert_eq!(paged.clone(), paged);
+    }
+}
+
+#[allow(dead_code)]
+mod take {
+    use view_types::views;
+
+    fn validate_ratio(ratio: &f32) -> bool {
+        *ratio >= 0.0 && *ratio <= 1.0
+    }
+
+    #[views(
+        #[view(take)]
+        pub view HybridSearch {
+            Some(query),
+            tags,
+            Some(ratio) if validate_ratio(ratio),
+        }
+        #[view(take)]
+        pub view Paged {
+            offset,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        tags: Vec<String>,
+        ratio: Option<f32>,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("test".to_owned()),
+            tags: vec!["rust".to_owned()],
+            ratio: Some(2.0),
+            offset: 5,
+            limit: 10,
+        };
+        assert!(search.take_hybrid_search().is_none());
+        assert_eq!(search.query.as_deref(), Some("test"));
+        assert_eq!(search.tags, ["rust"]);
+
+        search.ratio = Some(0.5);
+        let hybrid = search.take_hybrid_search().unwrap();
+        assert_eq!(hybrid.query, "test");
+        assert_eq!(hybrid.tags, ["rust"]);
+        assert_eq!(hybrid.ratio, 0.5);
+        assert_eq!(search.query, None);
+        assert!(search.tags.is_empty());
+        assert_eq!(search.ratio, None);
+        assert!(search.take_hybrid_search().is_none());
+
+        let paged = search.take_paged();
+        assert_eq!((paged.offset, paged.limit), (5, 10));
+        assert_eq!((search.offset, search.limit), (0, 0));
+    }
+}
+
+#[allow(dead_code)]
+mod from_iter {
+    use view_types::views;
+
+    #[views(
+        #[view(from_iter)]
+        pub view TagSearch {
+            tags,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        tags: Vec<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let tags = vec!["rust".to_owned(), "macros".to_owned()];
+        let search = tags.into_iter().collect::<TagSearch>();
+        assert_eq!(search.tags, ["rust", "macros"]);
+        assert_eq!(search.limit, 0);
+    }
+}
+
+#[allow(dead_code)]
+mod cache_validations {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use view_types::views;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn expensive_check(vector: &[u8]) -> bool {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        !vector.is_empty()
+    }
+
+    #[views(
+        cache_validations,
+        frag semantic {
+            Some(vector) if expensive_check(vector),
+        }
+        pub view SemanticSearch {
+            ..semantic,
+            limit,
+        }
+        pub view HybridSearch {
+            ..semantic,
+            Some(query),
+        }
+        pub view Paged {
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        vector: Option<Vec<u8>>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: None,
+            vector: Some(vec![1, 2, 3]),
+            limit: 10,
+        };
+        let kinds = search.satisfied_views();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            kinds,
+            [SearchVariantKind::SemanticSearch, SearchVariantKind::Paged]
+        );
+    }
+}
+
+mod shared_ownership {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    fn query_of(search: &Arc<Search>) -> Option<KeywordSearchRef<'_>> {
+        search.as_keyword_search()
+    }
+
+    #[test]
+    fn test() {
+        let search = Arc::new(Search {
+            query: Some("test".to_owned()),
+            limit: 10,
+        });
+        let keyword = query_of(&search).unwrap();
+        assert_eq!(keyword.query, "test");
+        assert_eq!(*keyword.limit, 10);
+
+        let shared = Arc::clone(&search);
+        let handle = std::thread::spawn(move || *shared.as_keyword_search().unwrap().limit);
+        assert_eq!(handle.join().unwrap(), 10);
+
+        let search = Rc::new(Search {
+            query: None,
+            limit: 5,
+        });
+        assert!(search.as_keyword_search().is_none());
+    }
+}
+
+#[allow(dead_code)]
+mod static_alias {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+            limit,
+        }
+        pub view SemanticSearch<'a> {
+            vector,
+            limit,
+        }
+    )]
+    #[Variant(static_alias)]
+    pub struct Search<'a> {
+        query: String,
+        limit: usize,
+        vector: &'a [u8],
+    }
+
+    fn keyword_variant(query: &str) -> SearchVariantStatic {
+        SearchVariant::KeywordSearch(KeywordSearch {
+            query: query.to_owned(),
+            limit: 10,
+        })
+    }
+
+    #[test]
+    fn test() {
+        let variants: Vec<SearchVariantStatic> = vec![
+            keyword_variant("test"),
+            SearchVariant::SemanticSearch(SemanticSearch {
+                vector: &[1, 2, 3],
+                limit: 5,
+            }),
+        ];
+        assert!(variants[0].is_keyword_search());
+        assert_eq!(variants[1].vector(), Some(&[1u8, 2, 3][..]));
+    }
+}
+
+mod ref_equality {
+    use view_types::views;
+
+    #[views(
+        #[Ref(
+            #[derive(PartialEq, Debug)]
+        )]
+        pub view HybridSearch<'a> {
+            #[view(str_ref)]
+            query,
+            vector,
+            tags,
+            Some(ratio),
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        vector: &'a Vec<u8>,
+        tags: &'a mut Vec<String>,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let (vector, other_vector) = (vec![1, 2, 3], vec![1, 2, 3]);
+        let (mut tags, mut other_tags) = (vec!["rust".to_owned()], vec!["rust".to_owned()]);
+        let search = Search {
+            query: "test".to_owned(),
+            vector: &vector,
+            tags: &mut tags,
+            ratio: Some(0.5),
+        };
+        let mut other = Search {
+            query: "test".to_owned(),
+            vector: &other_vector,
+            tags: &mut other_tags,
+            ratio: Some(0.5),
+        };
+
+        let hybrid = search.as_hybrid_search().unwrap();
+        assert!(!std::ptr::eq(hybrid.vector, &other_vector));
+        assert_eq!(hybrid, other.as_hybrid_search().unwrap());
+
+        other.ratio = Some(0.75);
+        assert_ne!(hybrid, other.as_hybrid_search().unwrap());
+    }
+}
+
+mod skip_ref {
+    use view_types::views;
+
+    #[views(
+        pub view Page {
+            #[view(skip_ref)]
+            offset,
+            #[view(skip_ref)]
+            Some(limit),
+            query,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        offset: usize,
+        limit: Option<usize>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: "test".to_owned(),
+            offset: 10,
+            limit: Some(20),
+        };
+
+        let page = search.as_page().unwrap();
+        let offset: usize = page.offset;
+        let limit: usize = page.limit;
+        let query: &String = page.query;
+        assert_eq!((offset, limit, query.as_str()), (10, 20, "test"));
+
+        let page_mut = search.as_page_mut().unwrap();
+        *page_mut.offset = 30;
+        assert_eq!(page_mut.downgrade().offset, 30);
+
+        let page = search.into_page().unwrap();
+        assert_eq!(page.as_ref().offset, 30);
+    }
+}
+
+#[allow(dead_code)]
+mod variant_mut_accessors {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch<'a> {
+            Some(query),
+            limit,
+            vector,
+        }
+        pub view HybridSearch {
+            Some(query),
+            limit,
+            ratio,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        limit: usize,
+        vector: &'a [u8],
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let mut variant = SearchVariant::HybridSearch(HybridSearch {
+            query: "hybrid".to_owned(),
+            limit: 10,
+            ratio: Some(0.5),
+        });
+        *variant.ratio_mut().unwrap() = 0.75;
+        *variant.limit_mut() += 5;
+        variant.query_mut().push('2');
+        let hybrid = HybridSearch::try_from(variant).ok().unwrap();
+        assert_eq!(hybrid.ratio, Some(0.75));
+        assert_eq!(hybrid.limit, 15);
+        assert_eq!(hybrid.query, "hybrid2");
+
+        let vector = [1, 2, 3];
+        let mut variant = SearchVariant::KeywordSearch(KeywordSearch {
+            query: "keyword".to_owned(),
+            limit: 10,
+            vector: &vector,
+        });
+        assert!(variant.ratio_mut().is_none());
+        *variant.limit_mut() = 20;
+        assert_eq!(*variant.limit(), 20);
+    }
+}
+
+#[allow(dead_code)]
+mod bench_helpers {
+    use view_types::views;
+
+    #[views(
+        bench_helpers,
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+        pub view Paged {
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let keyword = KeywordSearch {
+            query: "keyword".to_owned(),
+            limit: 10,
+        };
+        keyword.touch_all_fields();
+        SearchVariant::KeywordSearch(keyword).touch_all_fields();
+        SearchVariant::Paged(Paged { limit: 5 }).touch_all_fields();
+    }
+}
+
+#[allow(dead_code)]
+mod variant_hash {
+    use std::hash::{BuildHasher, RandomState};
+    use view_types::views;
+
+    #[views(
+        #[derive(Hash)]
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+        pub view HybridSearch {
+            limit,
+            Some(ratio),
+        }
+    )]
+    #[Variant(hash)]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let hasher = RandomState::new();
+        let keyword = |query: &str| {
+            SearchVariant::KeywordSearch(KeywordSearch {
+                query: query.to_owned(),
+                limit: 10,
+            })
+        };
+        assert_eq!(hasher.hash_one(keyword("rust")), hasher.hash_one(keyword("rust")));
+        assert_ne!(hasher.hash_one(keyword("rust")), hasher.hash_one(keyword("go")));
+
+        // `HybridSearch` is not `Hash`, so only its discriminant is hashed
+        let hybrid = |ratio: f32| SearchVariant::HybridSearch(HybridSearch { limit: 10, ratio });
+        assert_eq!(hasher.hash_one(hybrid(0.5)), hasher.hash_one(hybrid(0.75)));
+        assert_ne!(hasher.hash_one(hybrid(0.5)), hasher.hash_one(keyword("rust")));
+    }
+}
+
+#[allow(dead_code)]
+mod variant_into_accessors {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+        pub view Paged {
+            limit,
+            tags,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test() {
+        let query = "keyword".to_owned();
+        let query_ptr = query.as_ptr();
+        let variant = SearchVariant::KeywordSearch(KeywordSearch { query, limit: 10 });
+        let query: Option<String> = variant.into_query();
+        let query = query.unwrap();
+        assert_eq!(query, "keyword");
+        // Moved out, not cloned
+        assert_eq!(query.as_ptr(), query_ptr);
+
+        let variant = SearchVariant::Paged(Paged {
+            limit: 5,
+            tags: vec!["rust".to_owned()],
+        });
+        assert_eq!(variant.into_query(), None);
+        let variant = SearchVariant::Paged(Paged {
+            limit: 5,
+            tags: vec!["rust".to_owned()],
+        });
+        let limit: usize = variant.into_limit();
+        assert_eq!(limit, 5);
+    }
+}
+
+#[allow(dead_code)]
+mod debug_plan {
+    use view_types::views;
+
+    #[views(
+        debug_plan,
+        frag keyword {
+            Some(query) if !query.is_empty(),
+            limit,
+        }
+        pub view KeywordSearch<'a> {
+            ..keyword,
+            #[view(skip_ref)]
+            offset,
+            vector,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        limit: usize,
+        offset: usize,
+        vector: &'a [u8],
+    }
+
+    #[test]
+    fn test() {
+        let plan = Search::VIEW_PLAN.lines().collect::<Vec<_>>();
+        assert_eq!(plan[0], "view KeywordSearch<'a>, fallible");
+        assert!(plan[1].starts_with("    query: String, from frag keyword, matching Some, if "));
+        assert_eq!(plan[2], "        Ref: &'original String, Mut: &'original mut String");
+        assert_eq!(plan[5], "    offset: usize");
+        assert_eq!(plan[6], "        Ref: usize, Mut: &'original mut usize");
+        assert_eq!(plan[8], "        Ref: &'a [u8], Mut: &'a [u8]");
+    }
+}
+
+#[allow(dead_code)]
+mod variant_downcasts {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            limit,
+        }
+        pub view HybridSearch {
+            Some(query),
+            limit,
+            Some(ratio),
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let hybrid = HybridSearch {
+            query: "hybrid".to_owned(),
+            limit: 10,
+            ratio: 0.5,
+        };
+        let mut variant = SearchVariant::HybridSearch(hybrid);
+        assert!(variant.as_keyword_search().is_none());
+        let recovered = variant.as_hybrid_search().unwrap();
+        assert_eq!(recovered.query, "hybrid");
+        assert_eq!(recovered.ratio, 0.5);
+
+        variant.as_hybrid_search_mut().unwrap().ratio = 0.75;
+        assert!(variant.as_keyword_search_mut().is_none());
+        assert_eq!(variant.as_hybrid_search().unwrap().ratio, 0.75);
+    }
+}
+
+mod try_from_mut {
+    use view_types::views;
+
+    #[views(
+        pub view HybridSearch<'a> {
+            Some(query),
+            vector,
+            Some(ratio) if *ratio <= 1.0,
+        }
+        pub view Paged {
+            limit,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        vector: &'a [u8],
+        ratio: Option<f32>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2];
+        let mut search = Search {
+            query: Some("hybrid".to_owned()),
+            vector: &vector,
+            ratio: Some(0.5),
+            limit: 10,
+        };
+
+        let hybrid = HybridSearchMut::try_from(&mut search).unwrap();
+        hybrid.query.push('2');
+        assert_eq!(hybrid.vector, &[1, 2]);
+        *hybrid.ratio = 2.0;
+        assert_eq!(search.query.as_deref(), Some("hybrid2"));
+        assert_eq!(search.ratio, Some(2.0));
+        assert_eq!(HybridSearchMut::try_from(&mut search).err(), Some("ratio"));
+
+        search.ratio = None;
+        assert_eq!(HybridSearchMut::try_from(&mut search).err(), Some("ratio"));
+
+        let paged = PagedMut::from(&mut search);
+        *paged.limit = 20;
+        assert_eq!(search.limit, 20);
+    }
+}
+
+#[allow(dead_code)]
+mod variant_from_view {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+        }
+        pub view HybridSearch<'a> {
+            Some(query),
+            vector,
+            Some(ratio),
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        vector: &'a [u8],
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2, 3];
+        let hybrid = HybridSearch {
+            query: "hybrid".to_owned(),
+            vector: &vector,
+            ratio: 0.5,
+        };
+        let v: SearchVariant = hybrid.into();
+        assert!(v.is_hybrid_search());
+
+        let v = SearchVariant::from(KeywordSearch {
+            query: "keyword".to_owned(),
+        });
+        assert_eq!(v.query(), "keyword");
+    }
+}
+
+#[allow(dead_code)]
+mod raw_pointer {
+    use view_types::views;
+
+    #[views(
+        pub view Buffer {
+            data,
+            len,
+            Some(cursor),
+        }
+    )]
+    pub struct Ffi {
+        data: *const u8,
+        len: usize,
+        cursor: Option<*mut u8>,
+    }
+
+    #[test]
+    fn test() {
+        let mut bytes = [1u8, 2, 3];
+        let mut ffi = Ffi {
+            data: bytes.as_ptr(),
+            len: bytes.len(),
+            cursor: Some(bytes.as_mut_ptr()),
+        };
+
+        let buffer = ffi.as_buffer().unwrap();
+        let data: *const u8 = buffer.data;
+        let cursor: *mut u8 = buffer.cursor;
+        assert_eq!(data, cursor.cast_const());
+        assert_eq!(*buffer.len, 3);
+
+        let buffer = ffi.as_buffer_mut().unwrap();
+        *buffer.cursor = buffer.cursor.wrapping_add(1);
+        assert_eq!(ffi.cursor.unwrap().cast_const(), ffi.data.wrapping_add(1));
+    }
+}
+
+#[allow(dead_code)]
+mod variant_tag {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+        }
+        pub view Paged {
+            limit,
+        }
+        pub view HybridSearch {
+            Some(query),
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let keyword = SearchVariant::from(KeywordSearch {
+            query: "keyword".to_owned(),
+        });
+        let paged = SearchVariant::from(Paged { limit: 10 });
+        let hybrid = SearchVariant::from(HybridSearch {
+            query: "hybrid".to_owned(),
+            limit: 10,
+        });
+        assert_eq!(keyword.tag(), 0);
+        assert_eq!(paged.tag(), 1);
+        assert_eq!(hybrid.tag(), 2);
+    }
+}
+
+#[allow(dead_code)]
+mod view_to_original {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        #[derive(Debug, PartialEq)]
+        pub view Full<'a> {
+            ..all,
+            query,
+            vector,
+        }
+        pub view Extracted<'a> {
+            ..all,
+            Some(query),
+            vector,
+        }
+    )]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Search<'a> {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        vector: &'a [u8],
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2, 3];
+        let search = Search {
+            query: Some("query".to_owned()),
+            offset: 5,
+            limit: 10,
+            vector: &vector,
+        };
+        let full = search.clone().into_full();
+        assert_eq!(Search::from(full), search);
+        // `Extracted` unwrapped `query`, so it does not convert back
+        assert_eq!(search.into_extracted().unwrap().query, "query");
+    }
+}
+
+#[allow(dead_code)]
+mod to_owned_view {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        #[derive(Debug, PartialEq)]
+        pub view KeywordSearch {
+            ..all,
+            Some(query),
+        }
+        pub view SemanticSearch<'a> {
+            ..all,
+            vector,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        vector: &'a [u8],
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2, 3];
+        let search = Search {
+            query: Some("query".to_owned()),
+            offset: 5,
+            limit: 10,
+            vector: &vector,
+        };
+        let keyword: KeywordSearch = search.as_keyword_search().unwrap().to_owned_view();
+        assert_eq!(
+            keyword,
+            KeywordSearch {
+                offset: 5,
+                limit: 10,
+                query: "query".to_owned(),
+            }
+        );
+        let semantic = search.as_semantic_search().to_owned_view();
+        assert_eq!(semantic.vector, &vector);
+        // The original is still usable
+        assert_eq!(search.query.as_deref(), Some("query"));
+    }
+}
+
+#[allow(dead_code)]
+mod prefixed_spread {
+    use view_types::views;
+
+    #[views(
+        frag semantic {
+            query,
+            vector,
+        }
+        pub view SemanticSearch<'a> {
+            ..semantic as sem_,
+            limit,
+        }
+    )]
+    pub struct Search<'a> {
+        query: String,
+        vector: &'a [u8],
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let vector = [1, 2, 3];
+        let mut search = Search {
+            query: "query".to_owned(),
+            vector: &vector,
+            limit: 10,
+        };
+        let semantic_ref = search.as_semantic_search();
+        assert_eq!(semantic_ref.sem_query, "query");
+        assert_eq!(semantic_ref.sem_vector, &vector);
+        assert_eq!(*semantic_ref.limit, 10);
+
+        let semantic_mut = search.as_semantic_search_mut();
+        semantic_mut.sem_query.push_str("_mut");
+        *semantic_mut.limit = 20;
+        assert_eq!(search.query, "query_mut");
+
+        let semantic = search.into_semantic_search();
+        assert_eq!(semantic.sem_query, "query_mut");
+        assert_eq!(semantic.sem_vector, &vector);
+        assert_eq!(semantic.limit, 20);
+    }
+}
+
+mod reborrow {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        pub view KeywordSearch<'a> {
+            ..all,
+            Some(query),
+            mut_number,
+            vector,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        mut_number: &'a mut usize,
+        vector: &'a [u8],
+    }
+
+    fn advance(keyword: KeywordSearchMut<'_, '_>) {
+        *keyword.offset += *keyword.limit;
+        *keyword.mut_number += 1;
+        keyword.query.push('!');
+    }
+
+    #[test]
+    fn test() {
+        let mut number = 0;
+        let vector = [1, 2, 3];
+        let mut search = Search {
+            query: Some("query".to_owned()),
+            offset: 0,
+            limit: 10,
+            mut_number: &mut number,
+            vector: &vector,
+        };
+        let mut keyword = search.as_keyword_search_mut().unwrap();
+        advance(keyword.reborrow());
+        advance(keyword.reborrow());
+        assert_eq!(keyword.reborrow().vector, &vector);
+        assert_eq!(*keyword.offset, 20);
+        assert_eq!(search.query.as_deref(), Some("query!!"));
+        assert_eq!(*search.mut_number, 2);
+    }
+}
+
+mod validate_all {
+    use view_types::views;
+
+    fn validate_ratio(ratio: &f32) -> bool {
+        *ratio >= 0.0 && *ratio <= 1.0
+    }
+
+    #[views(
+        frag paging {
+            offset,
+            limit if *limit > 0,
+        } where valid = *offset < 100
+        pub view HybridSearch {
+            paging: ..paging,
+            Some(query),
+            Some(ratio) if validate_ratio(ratio),
+        } where valid = !query.is_empty()
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: None,
+            offset: 0,
+            limit: 10,
+            ratio: Some(2.0),
+        };
+        assert_eq!(search.validate_all_hybrid_search(), Err(vec!["query", "ratio"]));
+
+        search.limit = 0;
+        search.offset = 200;
+        // The fragment validation only runs once its fields hold
+        assert_eq!(search.validate_all_hybrid_search(), Err(vec!["limit", "query", "ratio"]));
+        search.limit = 10;
+        assert_eq!(search.validate_all_hybrid_search(), Err(vec!["paging", "query", "ratio"]));
+
+        search.offset = 0;
+        search.query = Some(String::new());
+        search.ratio = Some(0.5);
+        assert_eq!(search.validate_all_hybrid_search(), Err(vec!["valid"]));
+
+        search.query = Some("query".to_owned());
+        assert_eq!(search.validate_all_hybrid_search(), Ok(()));
+        assert!(search.into_hybrid_search().is_some());
+    }
+}
+
+#[allow(dead_code)]
+mod into_ref {
+    use view_types::views;
+
+    #[views(
+        frag paging {
+            offset,
+            limit,
+        }
+        pub view HybridSearch<'a> {
+            paging: ..paging,
+            #[view(str_ref)]
+            Some(query),
+            mut_number,
+            vector,
+        }
+    )]
+    pub struct Search<'a> {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        mut_number: &'a mut usize,
+        vector: &'a [u8],
+    }
+
+    fn bump<'original, 'a>(hybrid: HybridSearchMut<'original, 'a>) -> HybridSearchRef<'original, 'a> {
+        *hybrid.paging.offset += 1;
+        *hybrid.mut_number += 1;
+        hybrid.into_ref()
+    }
+
+    #[test]
+    fn test() {
+        let mut number = 0;
+        let vector = [1, 2, 3];
+        let mut search = Search {
+            query: Some("query".to_owned()),
+            offset: 0,
+            limit: 10,
+            mut_number: &mut number,
+            vector: &vector,
+        };
+        let hybrid = bump(search.as_hybrid_search_mut().unwrap());
+        // The `*Mut` struct is gone, but its `*Ref` struct still borrows the original
+        assert_eq!(*hybrid.paging.offset, 1);
+        assert_eq!(*hybrid.mut_number, 1);
+        assert_eq!(hybrid.query, "query");
+        assert_eq!(hybrid.vector, &vector);
+    }
+}
+
+mod ref_override {
+    use view_types::views;
+
+    #[derive(Debug, PartialEq)]
+    pub struct Bounds {
+        pub start: usize,
+        pub end: usize,
+    }
+
+    #[views(
+        frag paging {
+            #[view(ref_type = Bounds, ref_expr = Bounds { start: range.0, end: range.1 })]
+            range,
+        }
+        pub view KeywordSearch {
+            ..paging,
+            #[view(ref_type = &str, ref_expr = query.as_str())]
+            Some(query) if !query.is_empty(),
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        range: (usize, usize),
+    }
+
+    fn bounds(keyword: KeywordSearchRef<'_>) -> Bounds {
+        keyword.range
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("query".to_owned()),
+            range: (5, 10),
+        };
+        let keyword_ref = search.as_keyword_search().unwrap();
+        let query: &str = keyword_ref.query;
+        assert_eq!(query, "query");
+        assert_eq!(bounds(keyword_ref), Bounds { start: 5, end: 10 });
+
+        let keyword_mut = search.as_keyword_search_mut().unwrap();
+        keyword_mut.range.1 = 20;
+        assert_eq!(keyword_mut.downgrade().range, Bounds { start: 5, end: 20 });
+        assert_eq!(keyword_mut.into_ref().query, "query");
+
+        let keyword = search.into_keyword_search().unwrap();
+        assert_eq!(keyword.as_ref().range, Bounds { start: 5, end: 20 });
+        assert_eq!(keyword.as_ref().query, "query");
+    }
+}
+
+#[allow(dead_code)]
+mod common_field_names {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+        }
+        pub view KeywordSearch {
+            ..all,
+            Some(query),
+            ratio,
+        }
+        pub view SemanticSearch {
+            ..all,
+            ratio,
+        }
+        pub view HybridSearch {
+            ..all,
+            Some(query),
+            ratio,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    #[test]
+    fn test() {
+        // `query` is not in every view, and `ratio` is an `Option` in every view
+        assert_eq!(SearchVariant::common_field_names(), ["offset", "limit"]);
+        let semantic = SearchVariant::SemanticSearch(SemanticSearch {
+            offset: 5,
+            limit: 10,
+            ratio: None,
+        });
+        assert_eq!(*semantic.offset(), 5);
+        assert_eq!(semantic.query(), None);
+    }
+}
+
+#[allow(dead_code)]
+mod field_rename {
+    use view_types::views;
+
+    #[views(
+        #[derive(Debug, PartialEq)]
+        pub view KeywordSearch {
+            text = Some(query) if !text.is_empty(),
+            page_size = limit,
+        }
+        pub view Paged {
+            Some(query),
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("query".to_owned()),
+            limit: 10,
+        };
+        assert_eq!(search.as_keyword_search().unwrap().text, "query");
+        let keyword_mut = search.as_keyword_search_mut().unwrap();
+        *keyword_mut.page_size = 20;
+        keyword_mut.text.push('!');
+        assert_eq!(search.limit, 20);
+
+        let keyword = search.into_keyword_search().unwrap();
+        assert_eq!(
+            keyword,
+            KeywordSearch {
+                text: "query!".to_owned(),
+                page_size: 20,
+            }
+        );
+        let variant = SearchVariant::KeywordSearch(keyword);
+        assert_eq!(variant.text(), Some(&"query!".to_owned()));
+        assert_eq!(variant.page_size(), Some(&20));
+        assert_eq!(variant.query(), None);
+
+        let empty = Search {
+            query: Some(String::new()),
+            limit: 10,
+        };
+        assert!(empty.into_keyword_search().is_none());
+    }
+}
+
+#[allow(dead_code)]
+mod spread_except {
+    use view_types::views;
+
+    #[views(
+        frag all {
+            offset,
+            limit,
+            Some(query),
+        }
+        #[derive(Debug, PartialEq)]
+        pub view Unlimited {
+            ..all except { limit },
+        }
+        pub view Prefixed {
+            ..all except { offset, query } as page_,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: Some("query".to_owned()),
+            offset: 5,
+            limit: 10,
+        };
+        assert_eq!(search.as_prefixed().page_limit, &10);
+        // `limit` is absent, so the struct literal is complete without it
+        assert_eq!(
+            search.into_unlimited(),
+            Some(Unlimited {
+                offset: 5,
+                query: "query".to_owned(),
+            })
+        );
+    }
+}
+
+#[allow(dead_code)]
+mod guarded_mut {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query) if !query.is_empty(),
+            limit if *limit <= 100,
+        }
+        pub view HybridSearch {
+            limit if *limit <= 100,
+            Some(ratio),
+        } where valid = *ratio <= 1.0
+    )]
+    #[Variant(guarded_mut)]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+        ratio: Option<f32>,
+    }
+
+    fn keyword() -> SearchVariant {
+        SearchVariant::KeywordSearch(KeywordSearch {
+            query: "keyword".to_owned(),
+            limit: 10,
+        })
+    }
+
+    #[test]
+    fn test() {
+        let mut variant = keyword();
+        *variant.limit_mut() += 5;
+        variant.query_mut().unwrap().push('2');
+        assert!(variant.ratio_mut().is_none());
+        assert_eq!(*variant.limit(), 15);
+        assert_eq!(variant.query().unwrap(), "keyword2");
+
+        let mut variant = SearchVariant::HybridSearch(HybridSearch { limit: 10, ratio: 0.5 });
+        *variant.ratio_mut().unwrap() = 0.75;
+        assert_eq!(variant.ratio(), Some(&0.75));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invariant of `KeywordSearch` violated after mutation: `! query.is_empty()`")]
+    fn test_field_violation() {
+        let mut variant = keyword();
+        variant.query_mut().unwrap().clear();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invariant of `HybridSearch` violated after mutation: view validation")]
+    fn test_view_violation() {
+        let mut variant = SearchVariant::HybridSearch(HybridSearch { limit: 10, ratio: 0.5 });
+        *variant.ratio_mut().unwrap() = 2.0;
+    }
+}
+
+mod fragment_spread {
+    use view_types::views;
+
+    #[views(
+        frag base {
+            offset,
+        } where valid = *offset < 1000
+        frag paging {
+            ..base,
+            limit if *limit > 0,
+        }
+        frag search {
+            Some(query),
+            ..paging,
+        }
+        pub view KeywordSearch {
+            ..search,
+            words_limit,
+        }
+    )]
+    pub struct Search {
+        query: Option<String>,
+        offset: usize,
+        limit: usize,
+        words_limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: Some("rust".to_owned()),
+            offset: 10,
+            limit: 20,
+            words_limit: 5,
+        };
+        let keyword = search.as_keyword_search().unwrap();
+        assert_eq!(keyword.query, "rust");
+        assert_eq!(*keyword.offset, 10);
+        assert_eq!(*keyword.limit, 20);
+        assert_eq!(*keyword.words_limit, 5);
+
+        search.offset = 1000;
+        assert!(search.as_keyword_search().is_none());
+        search.offset = 0;
+        search.limit = 0;
+        assert!(search.as_keyword_search().is_none());
+    }
+}
+
+#[allow(dead_code)]
+mod define_views {
+    mod attribute {
+        use view_types::views;
+
+        #[views(
+            debug_plan,
+            frag paging {
+                offset,
+                limit,
+            }
+            pub view KeywordSearch<'a> {
+                ..paging,
+                Some(query) if !query.is_empty(),
+                vector,
+            }
+        )]
+        #[derive(Debug, Clone)]
+        pub struct Search<'a> {
+            pub query: Option<String>,
+            pub offset: usize,
+            pub limit: usize,
+            pub vector: &'a [u8],
+        }
+    }
+
+    mod function {
+        use view_types::define_views;
+
+        define_views! {
+            #[derive(Debug, Clone)]
+            pub struct Search<'a> {
+                pub query: Option<String>,
+                pub offset: usize,
+                pub limit: usize,
+                pub vector: &'a [u8],
+            }
+            fragments {
+                paging {
+                    offset,
+                    limit,
+                }
+            }
+            views {
+                debug_plan,
+                pub view KeywordSearch<'a> {
+                    ..paging,
+                    Some(query) if !query.is_empty(),
+                    vector,
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(attribute::Search::VIEW_PLAN, function::Search::VIEW_PLAN);
+
+        let vector = [1, 2, 3];
+        let search = function::Search {
+            query: Some("rust".to_owned()),
+            offset: 10,
+            limit: 20,
+            vector: &vector,
+        };
+        let keyword = search.clone().into_keyword_search().unwrap();
+        assert_eq!(keyword.query, "rust");
+        assert_eq!(keyword.vector, &vector);
+        assert!(matches!(
+            function::SearchVariant::from(keyword),
+            function::SearchVariant::KeywordSearch(_)
+        ));
+    }
+}
+
+#[allow(dead_code)]
+mod tuple_struct {
+    use view_types::views;
+
+    #[views(
+        pub view Named {
+            Some(field_0),
+            field_1,
+        }
+        pub view Whole {
+            field_0,
+            field_1,
+        }
+        pub view Sized {
+            size = field_1 if *size > 0,
+        }
+    )]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Labelled(Option<String>, usize);
+
+    #[test]
+    fn test() {
+        let mut labelled = Labelled(Some("label".to_owned()), 3);
+        let named = labelled.as_named().unwrap();
+        assert_eq!(named.field_0, "label");
+        assert_eq!(*named.field_1, 3);
+
+        *labelled.as_sized_mut().unwrap().size += 1;
+        assert_eq!(labelled.1, 4);
+
+        let whole = labelled.clone().into_whole();
+        assert_eq!(whole.field_0.as_deref(), Some("label"));
+        assert_eq!(Labelled::from(whole), labelled);
+
+        let named = labelled.into_named().unwrap();
+        assert_eq!(named.field_0, "label");
+        let variant = LabelledVariant::Named(named);
+        assert_eq!(variant.field_1(), Some(&4));
+    }
+}
+
+mod short_circuit {
+    use std::cell::Cell;
+    use view_types::views;
+
+    thread_local! {
+        static CHECKS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn counted(limit: &usize) -> bool {
+        CHECKS.with(|e| e.set(e.get() + 1));
+        *limit > 0
+    }
+
+    fn checks() -> usize {
+        CHECKS.with(|e| e.replace(0))
+    }
+
+    #[views(
+        pub view KeywordSearch {
+            Some(query),
+            limit if counted(limit),
+        } where valid = counted(limit)
+    )]
+    pub struct Search {
+        query: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: None,
+            limit: 10,
+        };
+        assert!(search.as_keyword_search().is_none());
+        assert!(search.as_keyword_search_mut().is_none());
+        assert_eq!(checks(), 0);
+        assert!(Search { query: None, limit: 10 }.into_keyword_search().is_none());
+        assert_eq!(checks(), 0);
+
+        search.query = Some("query".to_owned());
+        assert!(search.as_keyword_search().is_some());
+        assert_eq!(checks(), 2);
+        search.limit = 0;
+        assert!(search.as_keyword_search_mut().is_none());
+        assert_eq!(checks(), 1);
+    }
+}
+
+#[allow(dead_code)]
+mod inferred_generics {
+    use view_types::views;
+
+    #[views(
+        pub view Payload {
+            payload,
+            meta,
+        }
+        pub view Meta {
+            meta,
+        }
+        pub view Borrowed {
+            name,
+            extra,
+        }
+    )]
+    pub struct Wrapper<'a, T: Clone, U>
+    where
+        U: Default,
+    {
+        payload: T,
+        meta: usize,
+        name: &'a str,
+        extra: U,
+    }
+
+    fn payload<T: Clone>(view: &Payload<T>) -> T {
+        view.payload.clone()
+    }
+
+    #[test]
+    fn test() {
+        let mut wrapper = Wrapper {
+            payload: vec![1, 2],
+            meta: 3,
+            name: "name",
+            extra: 4u8,
+        };
+        let payload_ref: PayloadRef<'_, Vec<i32>> = wrapper.as_payload();
+        assert_eq!(payload_ref.payload, &vec![1, 2]);
+        let meta: MetaMut<'_> = wrapper.as_meta_mut();
+        *meta.meta += 1;
+        let borrowed: BorrowedRef<'_, '_, u8> = wrapper.as_borrowed();
+        assert_eq!(borrowed.name, "name");
+        assert_eq!(*borrowed.extra, 4);
+
+        let view = wrapper.into_payload();
+        assert_eq!(payload(&view), vec![1, 2]);
+        assert_eq!(view.meta, 4);
+        let variant: WrapperVariant<'_, Vec<i32>, u8> = view.into();
+        assert_eq!(variant.meta(), Some(&4));
+    }
+}
+
+#[allow(dead_code)]
+mod generic_payload {
+    use view_types::views;
+
+    #[views(
+        pub view Data {
+            data,
+            meta,
+        }
+        #[derive(Clone)]
+        pub view Meta {
+            meta if *meta > 0,
+        }
+    )]
+    #[derive(Clone)]
+    pub struct Wrapper<T: Clone + PartialEq> {
+        data: T,
+        meta: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut wrapper = Wrapper {
+            data: String::from("a"),
+            meta: 1,
+        };
+        let data_ref: DataRef<'_, String> = wrapper.as_data();
+        assert_eq!(data_ref.data, "a");
+        let data_mut: DataMut<'_, String> = wrapper.as_data_mut();
+        data_mut.data.push('b');
+
+        // `Meta` does not surface `T`, but the variant enum holding it does
+        let meta: Meta = wrapper.clone().into_meta().unwrap();
+        let variant: WrapperVariant<String> = meta.into();
+        assert_eq!(variant.data(), None);
+        assert_eq!(variant.meta(), &1);
+
+        let mut variant: WrapperVariant<String> = wrapper.into_data().into();
+        if let Some(data) = variant.data_mut() {
+            data.push('c');
+        }
+        assert_eq!(variant.data(), Some(&String::from("abc")));
+    }
+}
+
+#[allow(dead_code)]
+mod borrowed_fields {
+    use view_types::views;
+
+    #[views(
+        pub view Keyed {
+            &query,
+            &mut tags,
+            &Some(note) if !note.is_empty(),
+            limit,
+        }
+        pub view Paged {
+            query,
+            limit,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        tags: Vec<String>,
+        note: Option<String>,
+        limit: usize,
+    }
+
+    #[test]
+    fn test() {
+        let mut search = Search {
+            query: "query".to_owned(),
+            tags: Vec::new(),
+            note: Some("note".to_owned()),
+            limit: 10,
+        };
+        // Borrows `query`, `tags` and `note` from the original, and copies `limit` out of it
+        let mut keyed: Keyed<'_> = search.into_keyed().unwrap();
+        keyed.tags.push(keyed.query.clone());
+        assert_eq!(keyed.note, "note");
+        keyed.as_mut().tags.push("tag".to_owned());
+        assert_eq!(keyed.as_ref().tags.len(), 2);
+
+        let variant: SearchVariant<'_> = keyed.into();
+        assert_eq!(variant.query(), "query");
+        assert_eq!(variant.limit(), &10);
+        assert_eq!(search.tags, vec!["query".to_owned(), "tag".to_owned()]);
+
+        let keyed_mut: KeyedMut<'_> = search.as_keyed_mut().unwrap();
+        let _: &String = keyed_mut.query;
+        keyed_mut.tags.clear();
+
+        let paged: SearchVariant<'_> = search.into_paged().into();
+        assert_eq!(paged.query(), "query");
+    }
+}
+
+// Boxes the children too, covering views of a struct that holds itself behind a `Box`
+#[allow(dead_code, clippy::vec_box)]
+mod recursive_struct {
+    use view_types::views;
+
+    #[views(
+        deref(Box),
+        pub view Tree {
+            name,
+            children,
+        }
+        pub view Child {
+            name,
+            Some(parent),
+        }
+    )]
+    pub struct Search {
+        name: String,
+        children: Vec<Box<Search>>,
+        parent: Option<Box<Search>>,
+    }
+
+    fn search(name: &str) -> Search {
+        Search {
+            name: name.to_owned(),
+            children: Vec::new(),
+            parent: None,
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut root = search("root");
+        root.children.push(Box::new(search("child")));
+        let tree: TreeRef<'_> = root.as_tree();
+        let children: &Vec<Box<Search>> = tree.children;
+        assert_eq!(children[0].name, "child");
+        assert!(root.as_child().is_none());
+
+        let mut child = search("child");
+        child.parent = Some(Box::new(root));
+        let child_ref = child.as_child().unwrap();
+        let parent: &Search = child_ref.parent;
+        assert_eq!(parent.as_tree().children.len(), 1);
+
+        let variant: SearchVariant = child.into_child().unwrap().into();
+        assert_eq!(variant.name(), "child");
+        let parent: Option<&Box<Search>> = variant.parent();
+        assert_eq!(parent.unwrap().name, "root");
+    }
+}
+
+mod method_named_fields {
+    use view_types::views;
+
+    #[views(
+        pub view KeywordSearch {
+            query,
+            into_query,
+            kind,
+            common_fields,
+            accept,
+            fields_present,
+        }
+        pub view Paged {
+            query,
+            into_query,
+            kind,
+            common_fields,
+            accept,
+            fields_present,
+        }
+    )]
+    pub struct Search {
+        query: String,
+        into_query: bool,
+        kind: &'static str,
+        common_fields: usize,
+        accept: bool,
+        fields_present: u8,
+    }
+
+    #[test]
+    fn test() {
+        let search = Search {
+            query: "rust".to_owned(),
+            into_query: true,
+            kind: "keyword",
+            common_fields: 2,
+            accept: false,
+            fields_present: 3,
+        };
+        let variant: SearchVariant = search.into_keyword_search().into();
+        assert_eq!(variant.query(), "rust");
+        assert!(*variant.into_query());
+        assert_eq!(variant.kind(), "keyword");
+        assert_eq!(*variant.common_fields(), 2);
+        assert!(!*variant.accept());
+        assert_eq!(*variant.fields_present(), 3);
+    }
+}